@@ -0,0 +1,69 @@
+//! Avatar cache
+//!
+//! Avatar URLs by commit id, shared by all repositories. Only commits the GitHub
+//! API could not resolve are requested again.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::github;
+
+use super::store::{cache_dir, read_json, write_json};
+
+fn avatars() -> &'static Mutex<Option<HashMap<String, String>>> {
+    static AVATARS: OnceLock<Mutex<Option<HashMap<String, String>>>> = OnceLock::new();
+    AVATARS.get_or_init(|| Mutex::new(None))
+}
+
+/// Get avatar URLs for commits, fetching only the ones not cached yet
+pub async fn get_cached_avatars(remote_url: &str, commit_hashes: Vec<String>) -> Result<HashMap<String, String>, String> {
+    let path = cache_dir()?.join("avatars.json");
+
+    let is_loaded = avatars().lock().map(|a| a.is_some()).unwrap_or(false);
+    if !is_loaded {
+        let from_disk: HashMap<String, String> = read_json(&path).await;
+        if let Ok(mut a) = avatars().lock() {
+            a.get_or_insert(from_disk);
+        }
+    }
+
+    let mut results = HashMap::new();
+    let mut missing = Vec::new();
+    if let Ok(a) = avatars().lock() {
+        let cached = a.as_ref();
+        for hash in commit_hashes {
+            match cached.and_then(|c| c.get(&hash)) {
+                Some(url) => {
+                    results.insert(hash, url.clone());
+                }
+                None => missing.push(hash),
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(results);
+    }
+
+    let remote_url = remote_url.to_string();
+    let fetched = tokio::task::spawn_blocking(move || github::get_commit_avatars(&remote_url, missing))
+        .await
+        .map_err(|e| e.to_string())?;
+    if fetched.is_empty() {
+        return Ok(results);
+    }
+
+    let snapshot = match avatars().lock() {
+        Ok(mut a) => {
+            let cached = a.get_or_insert_with(HashMap::new);
+            cached.extend(fetched.iter().map(|(k, v)| (k.clone(), v.clone())));
+            cached.clone()
+        }
+        Err(_) => HashMap::new(),
+    };
+    if let Err(e) = write_json(&path, &snapshot).await {
+        eprintln!("Failed to save avatar cache: {}", e);
+    }
+
+    results.extend(fetched);
+    Ok(results)
+}
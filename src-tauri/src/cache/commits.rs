@@ -0,0 +1,166 @@
+//! Commit cache
+//!
+//! History pages are resolved to commit ids with `git rev-list`, which is cheap,
+//! and only commits missing from the cache are formatted with `git log`.
+//! Signatures are not cached: keys can expire or be revoked, so each page is
+//! verified again when it is served.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::git;
+use crate::git::executor::{execute_string, execute_with_input, GitResult};
+use crate::git::history::{parse_log_line, LOG_FORMAT};
+use crate::git::{CommitInfo, FileChange};
+
+use super::store::{repository_cache, save_repository_cache};
+
+/// Commits formatted per `git log` call
+const LOOKUP_BATCH: usize = 200;
+
+/// Format commits that are not cached yet, leaving their signatures unverified
+async fn fetch_commits(repo_path: &Path, ids: &[String]) -> GitResult<HashMap<String, CommitInfo>> {
+    let mut found = HashMap::new();
+
+    for chunk in ids.chunks(LOOKUP_BATCH) {
+        let format = format!("--format={}", LOG_FORMAT);
+        let mut args = vec!["log", "--no-walk=unsorted", "--date=iso-strict", &format];
+        args.extend(chunk.iter().map(|s| s.as_str()));
+        args.push("--");
+
+        let output = execute_string(repo_path, &args).await?;
+        for commit in output.lines().filter_map(parse_log_line) {
+            found.insert(commit.hash.clone(), commit);
+        }
+    }
+
+    Ok(found)
+}
+
+/// Fill in the signature status of `commits`
+async fn verify_signatures(repo_path: &Path, commits: &mut [CommitInfo]) -> GitResult<()> {
+    let hashes: Vec<String> = commits.iter().map(|c| c.hash.clone()).collect();
+    let mut verdicts = git::verify_commit_signatures(repo_path, &hashes).await?;
+    for commit in commits.iter_mut() {
+        if let Some(verdict) = verdicts.remove(&commit.hash) {
+            commit.signature = verdict.signature;
+            commit.signer = verdict.signer;
+        }
+    }
+    Ok(())
+}
+
+/// Get `limit` commits of HEAD's history starting `skip` commits from the tip
+///
+/// Same result as `git log --skip --max-count`, served from the cache where possible.
+pub async fn get_cached_history(repo_path: &Path, skip: usize, limit: usize) -> GitResult<Vec<CommitInfo>> {
+    let skip_arg = format!("--skip={}", skip);
+    let count_arg = format!("--max-count={}", limit);
+    let ids = execute_string(repo_path, &["rev-list", &skip_arg, &count_arg, "HEAD", "--"]).await?;
+    let ids: Vec<String> = ids.lines().map(|l| l.to_string()).collect();
+
+    let cache = repository_cache(repo_path).await;
+    let missing: Vec<String> = match cache.lock() {
+        Ok(c) => ids.iter().filter(|id| !c.commits.contains_key(*id)).cloned().collect(),
+        Err(_) => ids.clone(),
+    };
+
+    let mut fetched = HashMap::new();
+    if !missing.is_empty() {
+        fetched = fetch_commits(repo_path, &missing).await?;
+        if let Ok(mut c) = cache.lock() {
+            c.commits.extend(fetched.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        save_repository_cache(repo_path);
+    }
+
+    let mut commits: Vec<CommitInfo> = match cache.lock() {
+        Ok(c) => ids.iter().filter_map(|id| c.commits.get(id).cloned()).collect(),
+        Err(_) => ids.iter().filter_map(|id| fetched.get(id).cloned()).collect(),
+    };
+    verify_signatures(repo_path, &mut commits).await?;
+    Ok(commits)
+}
+
+/// Get the files changed in a commit, computing them only once per commit id and parent
+pub async fn get_cached_commit_files(repo_path: &Path, hash: &str, parent: Option<usize>) -> GitResult<Vec<FileChange>> {
+    let commit = format!("{}^{{commit}}", hash);
+    let id = execute_string(repo_path, &["rev-parse", "--verify", &commit]).await?;
+    let id = id.trim().to_string();
+    // The first parent is the common case and keeps the bare commit id as its key
+    let key = match parent {
+        Some(n) if n != 1 => format!("{}^{}", id, n),
+        _ => id.clone(),
+    };
+
+    let cache = repository_cache(repo_path).await;
+    if let Some(files) = cache.lock().ok().and_then(|c| c.files.get(&key).cloned()) {
+        return Ok(files);
+    }
+
+    let files = git::get_commit_files(repo_path, &id, parent).await?;
+    if let Ok(mut c) = cache.lock() {
+        c.files.insert(key, files.clone());
+    }
+    save_repository_cache(repo_path);
+    Ok(files)
+}
+
+/// Add every commit reachable from branches and tags to the cache
+///
+/// Only history that is new since the last run is walked: refs whose tip is already
+/// indexed are excluded with `^tip`. Returns the number of commits added.
+pub async fn index_repository_history(repo_path: &Path) -> GitResult<usize> {
+    let refs = execute_string(
+        repo_path,
+        &["for-each-ref", "--format=%(refname)%09%(objectname)%09%(objecttype)", "refs/heads", "refs/remotes", "refs/tags"],
+    )
+    .await?;
+    let tips: HashMap<String, String> = refs
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.split('\t');
+            let (name, id, kind) = (parts.next()?, parts.next()?, parts.next()?);
+            // Annotated tags point at tag objects; rev-list peels them itself
+            matches!(kind, "commit" | "tag").then(|| (name.to_string(), id.to_string()))
+        })
+        .collect();
+
+    let cache = repository_cache(repo_path).await;
+    let indexed: HashSet<String> = match cache.lock() {
+        Ok(c) => c.indexed_tips.values().cloned().collect(),
+        Err(_) => HashSet::new(),
+    };
+
+    let new_tips: Vec<&String> = tips.values().filter(|id| !indexed.contains(*id)).collect();
+    if new_tips.is_empty() {
+        return Ok(0);
+    }
+
+    let mut args: Vec<String> = vec!["rev-list".to_string()];
+    args.extend(new_tips.iter().map(|id| id.to_string()));
+    // Old tips may have been garbage collected after a force push
+    if !indexed.is_empty() {
+        let input: String = indexed.iter().map(|id| format!("{}\n", id)).collect();
+        let found = execute_with_input(repo_path, &["cat-file", "--batch-check=%(objectname)"], &input).await?;
+        args.extend(found.lines().filter(|l| !l.ends_with(" missing")).map(|id| format!("^{}", id)));
+    }
+    args.push("--".to_string());
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let ids = execute_string(repo_path, &arg_refs).await?;
+
+    let missing: Vec<String> = match cache.lock() {
+        Ok(c) => ids.lines().filter(|id| !c.commits.contains_key(*id)).map(|s| s.to_string()).collect(),
+        Err(_) => return Ok(0),
+    };
+    let fetched = fetch_commits(repo_path, &missing).await?;
+    let added = fetched.len();
+
+    if let Ok(mut c) = cache.lock() {
+        c.commits.extend(fetched);
+        c.indexed_tips = tips;
+    }
+    save_repository_cache(repo_path);
+
+    Ok(added)
+}
@@ -0,0 +1,12 @@
+//! Cache module
+//!
+//! On-disk caches of commit metadata, changed files and avatars, so history
+//! views do not re-run git or hit the network for data that never changes.
+
+pub mod avatars;
+pub mod commits;
+pub mod store;
+
+pub use avatars::get_cached_avatars;
+pub use commits::{get_cached_commit_files, get_cached_history, index_repository_history};
+pub use store::clear_repository_cache;
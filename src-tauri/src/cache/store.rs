@@ -0,0 +1,178 @@
+//! Cache storage
+//!
+//! Per-repository cache files under the pinax cache directory. Commit ids are
+//! immutable, so entries never need invalidation; a version bump discards old files.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::git::{CommitInfo, FileChange};
+
+/// Layout version of the cache files
+const CACHE_VERSION: u32 = 3;
+
+/// How long changes are collected before the cache file is rewritten
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// Cached data of one repository
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepositoryCache {
+    pub version: u32,
+    /// Commit metadata by object id, without signature verdicts
+    pub commits: HashMap<String, CommitInfo>,
+    /// Changed files by commit id
+    pub files: HashMap<String, Vec<FileChange>>,
+    /// Ref tips whose whole history is in `commits`, by ref name
+    pub indexed_tips: HashMap<String, String>,
+}
+
+type SharedCache = Arc<Mutex<RepositoryCache>>;
+
+fn loaded() -> &'static Mutex<HashMap<PathBuf, SharedCache>> {
+    static LOADED: OnceLock<Mutex<HashMap<PathBuf, SharedCache>>> = OnceLock::new();
+    LOADED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Repositories with a cache write scheduled
+fn pending_saves() -> &'static Mutex<HashSet<PathBuf>> {
+    static PENDING: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Root of all pinax caches
+pub fn cache_dir() -> Result<PathBuf, String> {
+    let dir = dirs::cache_dir().ok_or("Could not find cache directory")?;
+    Ok(dir.join("pinax"))
+}
+
+/// Cache file of a repository: readable name plus a hash of the full path
+fn repository_cache_path(repo_path: &Path) -> Result<PathBuf, String> {
+    let mut hasher = DefaultHasher::new();
+    repo_path.hash(&mut hasher);
+    let name = repo_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repository".to_string());
+
+    Ok(cache_dir()?
+        .join("repositories")
+        .join(format!("{}-{:016x}.json", name, hasher.finish())))
+}
+
+/// Read a JSON cache file, treating missing, corrupt or outdated files as empty
+pub async fn read_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match fs::read_to_string(path).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => T::default(),
+    }
+}
+
+/// Write a JSON cache file through a temp file, so readers never see a partial file
+pub async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_string(value).map_err(|e| format!("Failed to serialize cache: {}", e))?;
+    write_content(path, content).await
+}
+
+async fn write_content(path: &Path, content: String) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, content)
+        .await
+        .map_err(|e| format!("Failed to write cache: {}", e))?;
+    fs::rename(&tmp, path)
+        .await
+        .map_err(|e| format!("Failed to write cache: {}", e))
+}
+
+/// Get the cache of a repository, loading it from disk on first use
+pub async fn repository_cache(repo_path: &Path) -> SharedCache {
+    if let Some(cache) = loaded().lock().ok().and_then(|l| l.get(repo_path).cloned()) {
+        return cache;
+    }
+
+    let mut cache: RepositoryCache = match repository_cache_path(repo_path) {
+        Ok(path) => read_json(&path).await,
+        Err(_) => RepositoryCache::default(),
+    };
+    if cache.version != CACHE_VERSION {
+        cache = RepositoryCache {
+            version: CACHE_VERSION,
+            ..Default::default()
+        };
+    }
+
+    let shared = Arc::new(Mutex::new(cache));
+    match loaded().lock() {
+        // Another task may have loaded it meanwhile; keep the first copy
+        Ok(mut l) => l.entry(repo_path.to_path_buf()).or_insert(shared).clone(),
+        Err(_) => shared,
+    }
+}
+
+/// Persist the cache of a repository in the background
+///
+/// Writes are debounced by `SAVE_DELAY`, so a burst of cache misses rewrites the
+/// file once. Losing a pending write only costs recomputing the entries.
+pub fn save_repository_cache(repo_path: &Path) {
+    // A write already scheduled will pick up this change too
+    let scheduled = pending_saves()
+        .lock()
+        .map(|mut pending| pending.insert(repo_path.to_path_buf()))
+        .unwrap_or(false);
+    if !scheduled {
+        return;
+    }
+
+    let repo_path = repo_path.to_path_buf();
+    tokio::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if let Ok(mut pending) = pending_saves().lock() {
+            pending.remove(&repo_path);
+        }
+
+        // A cache cleared in the meantime must not be written back
+        let Some(cache) = loaded().lock().ok().and_then(|l| l.get(&repo_path).cloned()) else {
+            return;
+        };
+        // Serialize under the lock instead of cloning the whole cache
+        let content = match cache.lock() {
+            Ok(c) => serde_json::to_string(&*c).map_err(|e| format!("Failed to serialize cache: {}", e)),
+            Err(_) => return,
+        };
+
+        let result = match (content, repository_cache_path(&repo_path)) {
+            (Ok(content), Ok(path)) => write_content(&path, content).await,
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to save cache for {}: {}", repo_path.display(), e);
+        }
+    });
+}
+
+/// Drop the cached data of a repository from memory and disk
+pub async fn clear_repository_cache(repo_path: &Path) -> Result<(), String> {
+    if let Ok(mut l) = loaded().lock() {
+        l.remove(repo_path);
+    }
+
+    let path = repository_cache_path(repo_path)?;
+    match fs::remove_file(&path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove cache: {}", e)),
+    }
+}
@@ -0,0 +1,190 @@
+//! Git authors module
+//!
+//! Builds a per-repository index of commit authors for the co-author picker.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute_string, GitResult};
+
+/// An author known to a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorEntry {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+}
+
+impl AuthorEntry {
+    /// Format as `Name <email>`, the form used by `Co-authored-by`
+    pub fn identity(&self) -> String {
+        format!("{} <{}>", self.name, self.email)
+    }
+}
+
+/// A search hit with its fuzzy match score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorMatch {
+    #[serde(flatten)]
+    pub author: AuthorEntry,
+    pub score: i64,
+}
+
+/// Cached index per repository, keyed by the state of its refs
+struct CachedIndex {
+    refs: String,
+    authors: Vec<AuthorEntry>,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedIndex>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedIndex>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Get the author index for a repository, rebuilding it when refs have moved
+pub async fn get_author_index(repo_path: &Path) -> GitResult<Vec<AuthorEntry>> {
+    let refs = execute_string(repo_path, &["for-each-ref", "--format=%(objectname)"]).await?;
+    let mailmap = tokio::fs::read_to_string(repo_path.join(".mailmap")).await.unwrap_or_default();
+    let key = format!("{}\n{}", refs, mailmap);
+
+    if let Ok(cache) = cache().lock() {
+        if let Some(cached) = cache.get(repo_path) {
+            if cached.refs == key {
+                return Ok(cached.authors.clone());
+            }
+        }
+    }
+
+    let authors = build_author_index(repo_path, &mailmap).await?;
+
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(
+            repo_path.to_path_buf(),
+            CachedIndex {
+                refs: key,
+                authors: authors.clone(),
+            },
+        );
+    }
+
+    Ok(authors)
+}
+
+/// Build the author index from `git shortlog` and `.mailmap`
+async fn build_author_index(repo_path: &Path, mailmap: &str) -> GitResult<Vec<AuthorEntry>> {
+    // shortlog already applies .mailmap to the identities it reports; deleted branches
+    // kept for restore are not part of the history
+    let output = execute_string(repo_path, &["shortlog", "-sne", "--exclude=refs/pinax/*", "--all"]).await?;
+
+    let mut authors: Vec<AuthorEntry> = Vec::new();
+    for line in output.lines() {
+        if let Some(entry) = parse_shortlog_line(line) {
+            authors.push(entry);
+        }
+    }
+
+    // Canonical identities from .mailmap are offered even without commits under them
+    for (name, email) in parse_mailmap(mailmap) {
+        let known = authors.iter().any(|a| a.email.eq_ignore_ascii_case(&email));
+        if !known {
+            authors.push(AuthorEntry {
+                name,
+                email,
+                commits: 0,
+            });
+        }
+    }
+
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    Ok(authors)
+}
+
+/// Parse a `   42\tName <email>` line from `git shortlog -sne`
+fn parse_shortlog_line(line: &str) -> Option<AuthorEntry> {
+    let (count, identity) = line.trim().split_once('\t')?;
+    let (name, email) = split_identity(identity)?;
+    Some(AuthorEntry {
+        name,
+        email,
+        commits: count.trim().parse().unwrap_or(0),
+    })
+}
+
+/// Split `Name <email>` into its parts
+fn split_identity(identity: &str) -> Option<(String, String)> {
+    let start = identity.find('<')?;
+    let end = identity[start..].find('>')? + start;
+    let name = identity[..start].trim().to_string();
+    let email = identity[start + 1..end].trim().to_string();
+    if name.is_empty() || email.is_empty() {
+        return None;
+    }
+    Some((name, email))
+}
+
+/// Extract the canonical `Proper Name <proper@email>` of each `.mailmap` entry
+fn parse_mailmap(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or_default().trim())
+        .filter(|l| !l.is_empty())
+        .filter_map(split_identity)
+        .collect()
+}
+
+/// Fuzzy search the author index of a repository
+pub async fn search_authors(repo_path: &Path, query: &str, limit: usize) -> GitResult<Vec<AuthorMatch>> {
+    let authors = get_author_index(repo_path).await?;
+    let query = query.trim().to_lowercase();
+
+    let mut matches: Vec<AuthorMatch> = authors
+        .into_iter()
+        .filter_map(|author| {
+            let score = if query.is_empty() {
+                0
+            } else {
+                fuzzy_score(&author.identity().to_lowercase(), &query)?
+            };
+            Some(AuthorMatch { author, score })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| b.author.commits.cmp(&a.author.commits))
+    });
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Score `query` as a subsequence of `text`, rewarding runs and word starts
+fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut last_match: Option<usize> = None;
+
+    for qc in query.chars() {
+        let found = (pos..text.len()).find(|&i| text[i] == qc)?;
+
+        score += 1;
+        if last_match.map(|l| l + 1 == found).unwrap_or(false) {
+            score += 5;
+        }
+        if found == 0 || matches!(text[found - 1], ' ' | '<' | '.' | '-' | '_' | '@') {
+            score += 3;
+        }
+        if let Some(l) = last_match {
+            score -= (found - l - 1).min(5) as i64;
+        }
+
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score)
+}
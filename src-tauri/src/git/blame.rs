@@ -0,0 +1,289 @@
+//! Git blame module
+//!
+//! Line ownership from `git blame --porcelain --incremental`, delivered range by
+//! range as git produces them.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute_lines_until, execute_string, GitResult};
+
+/// Conventional ignore file used when `blame.ignoreRevsFile` is not configured
+const DEFAULT_IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+
+/// Options for a blame run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlameOptions {
+    /// Blame as of this revision instead of the working tree, e.g. `<hash>^` to
+    /// look at the lines before a commit
+    pub revision: Option<String>,
+    /// Ignore whitespace changes (`-w`)
+    pub ignore_whitespace: bool,
+    /// Detect lines moved within the file (`-M`)
+    pub detect_moves: bool,
+    /// Detect lines copied from other files (`-C`), repeated up to three times
+    pub detect_copies: u8,
+    /// Skip revisions listed in `blame.ignoreRevsFile` or `.git-blame-ignore-revs`
+    pub use_ignore_revs: bool,
+}
+
+impl Default for BlameOptions {
+    fn default() -> Self {
+        Self {
+            revision: None,
+            ignore_whitespace: false,
+            detect_moves: false,
+            detect_copies: 0,
+            use_ignore_revs: true,
+        }
+    }
+}
+
+/// A run of consecutive lines last changed by the same commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameRange {
+    pub hash: String,
+    /// First line of the range in the blamed file, 1-based
+    pub final_line: u32,
+    /// First line of the range in the file as of `hash`
+    pub original_line: u32,
+    pub line_count: u32,
+    pub author: String,
+    pub author_email: String,
+    /// Unix timestamp
+    pub author_time: i64,
+    pub summary: String,
+    /// Path of the file in `hash`, which differs after renames
+    pub path: String,
+    /// Parent commit and path to continue blaming from ("blame prior to this commit")
+    pub previous_hash: Option<String>,
+    pub previous_path: Option<String>,
+    /// The commit is a boundary of the blamed range, e.g. the root commit
+    pub boundary: bool,
+}
+
+/// Commit headers are only printed the first time a commit appears
+#[derive(Debug, Clone, Default)]
+struct CommitHeader {
+    author: String,
+    author_email: String,
+    author_time: i64,
+    summary: String,
+    boundary: bool,
+}
+
+/// Incremental parser for `--porcelain --incremental` output
+#[derive(Default)]
+struct BlameParser {
+    commits: HashMap<String, CommitHeader>,
+    current: Option<BlameRange>,
+}
+
+impl BlameParser {
+    /// Feed one line; returns a range once its `filename` line has been read
+    fn push(&mut self, line: &str) -> Option<BlameRange> {
+        let Some(range) = self.current.as_mut() else {
+            // `<hash> <original line> <final line> <line count>`
+            let parts: Vec<&str> = line.split(' ').collect();
+            if parts.len() == 4 && parts[0].len() >= 40 {
+                let header = self.commits.entry(parts[0].to_string()).or_default().clone();
+                self.current = Some(BlameRange {
+                    hash: parts[0].to_string(),
+                    original_line: parts[1].parse().unwrap_or(0),
+                    final_line: parts[2].parse().unwrap_or(0),
+                    line_count: parts[3].parse().unwrap_or(0),
+                    author: header.author,
+                    author_email: header.author_email,
+                    author_time: header.author_time,
+                    summary: header.summary,
+                    path: String::new(),
+                    previous_hash: None,
+                    previous_path: None,
+                    boundary: header.boundary,
+                });
+            }
+            return None;
+        };
+
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let header = self.commits.entry(range.hash.clone()).or_default();
+        match key {
+            "author" => {
+                header.author = value.to_string();
+                range.author = value.to_string();
+            }
+            "author-mail" => {
+                let email = value.trim_start_matches('<').trim_end_matches('>').to_string();
+                header.author_email = email.clone();
+                range.author_email = email;
+            }
+            "author-time" => {
+                let time = value.parse().unwrap_or(0);
+                header.author_time = time;
+                range.author_time = time;
+            }
+            "summary" => {
+                header.summary = value.to_string();
+                range.summary = value.to_string();
+            }
+            "boundary" => {
+                header.boundary = true;
+                range.boundary = true;
+            }
+            "previous" => {
+                if let Some((hash, path)) = value.split_once(' ') {
+                    range.previous_hash = Some(hash.to_string());
+                    range.previous_path = Some(path.to_string());
+                }
+            }
+            "filename" => {
+                let mut range = self.current.take()?;
+                range.path = value.to_string();
+                return Some(range);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// `--ignore-revs-file` arguments: the configured file when it exists, otherwise the
+/// conventional `.git-blame-ignore-revs`, or an explicit reset when disabled
+async fn ignore_revs_args(repo_path: &Path, enabled: bool) -> Vec<String> {
+    // An empty value clears the list git would otherwise read from config
+    let reset = vec!["--ignore-revs-file=".to_string()];
+    if !enabled {
+        return reset;
+    }
+
+    match execute_string(repo_path, &["config", "--path", "blame.ignoreRevsFile"]).await {
+        // git refuses to blame when the configured file is missing
+        Ok(file) if !file.trim().is_empty() => {
+            let file = file.trim();
+            if repo_path.join(file).is_file() || Path::new(file).is_file() {
+                Vec::new()
+            } else {
+                reset
+            }
+        }
+        _ if repo_path.join(DEFAULT_IGNORE_REVS_FILE).is_file() => {
+            vec![format!("--ignore-revs-file={}", DEFAULT_IGNORE_REVS_FILE)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Blame runs that were cancelled and should stop
+fn cancelled() -> &'static Mutex<HashSet<String>> {
+    static CANCELLED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    CANCELLED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Stop a streamed blame, including one that has not started yet
+pub fn cancel_blame(blame_id: &str) {
+    if let Ok(mut set) = cancelled().lock() {
+        set.insert(blame_id.to_string());
+    }
+}
+
+fn is_cancelled(blame_id: &str) -> bool {
+    cancelled().lock().map(|set| set.contains(blame_id)).unwrap_or(false)
+}
+
+/// Blame a file, handing each range to `on_range` as git reports it
+///
+/// Ranges arrive in no particular order; `on_range` returning `false` stops the run.
+pub async fn blame_file_with<F>(repo_path: &Path, file_path: &str, options: &BlameOptions, on_range: F) -> GitResult<()>
+where
+    F: FnMut(BlameRange) -> bool,
+{
+    blame_file_until(repo_path, file_path, options, || false, on_range).await
+}
+
+/// Like `blame_file_with`, but also stops as soon as `should_stop` returns `true`,
+/// even while git is still computing the first range
+async fn blame_file_until<S, F>(
+    repo_path: &Path,
+    file_path: &str,
+    options: &BlameOptions,
+    should_stop: S,
+    mut on_range: F,
+) -> GitResult<()>
+where
+    S: Fn() -> bool,
+    F: FnMut(BlameRange) -> bool,
+{
+    let mut args: Vec<String> = vec!["blame".to_string(), "--porcelain".to_string(), "--incremental".to_string()];
+    if options.ignore_whitespace {
+        args.push("-w".to_string());
+    }
+    if options.detect_moves {
+        args.push("-M".to_string());
+    }
+    for _ in 0..options.detect_copies.min(3) {
+        args.push("-C".to_string());
+    }
+    args.extend(ignore_revs_args(repo_path, options.use_ignore_revs).await);
+    if let Some(revision) = options.revision.as_deref().filter(|r| !r.is_empty()) {
+        args.push(revision.to_string());
+    }
+    args.push("--".to_string());
+    args.push(file_path.to_string());
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let mut parser = BlameParser::default();
+    execute_lines_until(repo_path, &arg_refs, should_stop, |line| match parser.push(line) {
+        Some(range) => on_range(range),
+        None => true,
+    })
+    .await
+}
+
+/// Blame a file, delivering ranges in batches of `batch_size` until done or cancelled
+///
+/// `on_batch` receives each batch and whether it is the last one.
+pub async fn blame_file_batched<F>(
+    repo_path: &Path,
+    blame_id: &str,
+    file_path: &str,
+    options: &BlameOptions,
+    batch_size: usize,
+    mut on_batch: F,
+) -> GitResult<()>
+where
+    F: FnMut(Vec<BlameRange>, bool),
+{
+    // Not cleared here: a cancel may arrive before the run starts
+    let mut batch = Vec::new();
+    let result = blame_file_until(repo_path, file_path, options, || is_cancelled(blame_id), |range| {
+        batch.push(range);
+        if batch.len() >= batch_size.max(1) {
+            on_batch(std::mem::take(&mut batch), false);
+        }
+        !is_cancelled(blame_id)
+    })
+    .await;
+
+    on_batch(batch, true);
+    if let Ok(mut set) = cancelled().lock() {
+        set.remove(blame_id);
+    }
+    result
+}
+
+/// Blame a file and return all ranges in line order
+pub async fn blame_file(repo_path: &Path, file_path: &str, options: &BlameOptions) -> GitResult<Vec<BlameRange>> {
+    let mut ranges = Vec::new();
+    blame_file_with(repo_path, file_path, options, |range| {
+        ranges.push(range);
+        true
+    })
+    .await?;
+
+    ranges.sort_by_key(|r| r.final_line);
+    Ok(ranges)
+}
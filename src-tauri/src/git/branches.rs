@@ -2,14 +2,70 @@
 //!
 //! Functions for listing and working with Git branches.
 
+use std::collections::HashSet;
 use std::path::Path;
 
-use super::executor::{execute_string, GitResult};
+use super::executor::{ensure_writable, execute_string, GitError, GitResult};
+use super::naming::ensure_new_branch_name;
 use super::types::Branch;
 
+/// Ahead, behind and gone state from `%(upstream:track)`, e.g. `[ahead 1, behind 2]`
+fn parse_track(track: &str) -> (u32, u32, bool) {
+    let inner = track.trim().trim_start_matches('[').trim_end_matches(']');
+    if inner == "gone" {
+        return (0, 0, true);
+    }
+
+    let mut ahead = 0;
+    let mut behind = 0;
+    for part in inner.split(',') {
+        let mut words = part.split_whitespace();
+        match (words.next(), words.next().and_then(|n| n.parse().ok())) {
+            (Some("ahead"), Some(n)) => ahead = n,
+            (Some("behind"), Some(n)) => behind = n,
+            _ => {}
+        }
+    }
+    (ahead, behind, false)
+}
+
+/// Refs whose tip is reachable from the default branch
+async fn merged_refs(repo_path: &Path) -> HashSet<String> {
+    let Ok(Some(default)) = get_default_branch(repo_path).await else {
+        return HashSet::new();
+    };
+
+    // The remote copy is authoritative when the local default branch is stale or missing
+    let local = format!("refs/heads/{}", default);
+    let remote = format!("refs/remotes/origin/{}", default);
+    let mut merged = HashSet::new();
+    for target in [local, remote] {
+        let merged_arg = format!("--merged={}", target);
+        if let Ok(output) = execute_string(
+            repo_path,
+            &["for-each-ref", &merged_arg, "--format=%(refname)", "refs/heads", "refs/remotes"],
+        )
+        .await
+        {
+            merged.extend(output.lines().map(|l| l.to_string()));
+        }
+    }
+    merged
+}
+
 /// List all branches in a repository
 pub async fn list_branches(repo_path: &Path) -> GitResult<Vec<Branch>> {
-    let output = execute_string(repo_path, &["branch", "-a", "--format=%(refname)\t%(HEAD)\t%(upstream:short)\t%(upstream:track)"]).await?;
+    let output = execute_string(
+        repo_path,
+        &[
+            "for-each-ref",
+            "--format=%(refname)\t%(HEAD)\t%(upstream:short)\t%(upstream:track)\t%(committerdate:iso-strict)\t%(authorname)",
+            "refs/heads",
+            "refs/remotes",
+        ],
+    )
+    .await?;
+    let merged = merged_refs(repo_path).await;
 
     let mut branches = Vec::new();
 
@@ -21,24 +77,14 @@ pub async fn list_branches(repo_path: &Path) -> GitResult<Vec<Branch>> {
 
         let refname = parts[0].to_string();
         let is_current = parts.get(1).map(|s| *s == "*").unwrap_or(false);
-        let upstream = parts.get(2).and_then(|s| {
-            if s.is_empty() {
-                None
-            } else {
-                Some(s.to_string())
-            }
-        });
-        let track = parts.get(3).and_then(|s| {
-            if s.is_empty() {
-                None
-            } else {
-                Some(s.to_string())
-            }
-        });
+        let field = |i: usize| parts.get(i).filter(|s| !s.is_empty()).map(|s| s.to_string());
+        let upstream = field(2);
+        let track = field(3);
+        let (ahead, behind, upstream_gone) = track.as_deref().map(parse_track).unwrap_or((0, 0, false));
 
         // refs/heads/master or refs/remotes/origin/master
         let is_remote = refname.starts_with("refs/remotes/");
-        
+
         // Extract short name for display
         let name = if refname.starts_with("refs/heads/") {
             refname.trim_start_matches("refs/heads/").to_string()
@@ -49,18 +95,142 @@ pub async fn list_branches(repo_path: &Path) -> GitResult<Vec<Branch>> {
         };
 
         // Filter out HEAD symrefs (e.g. origin/HEAD)
-        if name.ends_with("/HEAD") {
+        if name.ends_with("/HEAD") || name == "HEAD" {
             continue;
         }
 
         branches.push(Branch {
+            merged: merged.contains(&refname),
             name,
             is_current,
             is_remote,
             upstream,
             track,
+            last_commit_date: field(4),
+            last_commit_author: field(5),
+            ahead,
+            behind,
+            upstream_gone,
         });
     }
 
     Ok(branches)
 }
+
+/// Create a branch at any commit-ish without checking it out
+///
+/// `track` sets up tracking when `start_point` is a remote-tracking branch; `None`
+/// leaves it to `branch.autoSetupMerge`.
+pub async fn create_branch_at(repo_path: &Path, name: &str, start_point: &str, track: Option<bool>) -> GitResult<()> {
+    ensure_writable(repo_path, "branch").await?;
+    let mut args = vec!["branch"];
+    match track {
+        Some(true) => args.push("--track"),
+        Some(false) => args.push("--no-track"),
+        None => {}
+    }
+    args.extend_from_slice(&[name, start_point]);
+
+    ensure_new_branch_name(repo_path, name).await?;
+    execute_string(repo_path, &args).await?;
+    Ok(())
+}
+
+/// Rename a local branch, optionally renaming its upstream on the remote too
+///
+/// The remote rename pushes the branch under its new name with tracking and then
+/// deletes the old remote branch.
+pub async fn rename_branch(repo_path: &Path, old_name: &str, new_name: &str, rename_remote: bool) -> GitResult<()> {
+    ensure_writable(repo_path, "branch -m").await?;
+    // Read the upstream before the rename moves the branch config
+    let remote = execute_string(repo_path, &["config", &format!("branch.{}.remote", old_name)])
+        .await
+        .ok();
+    let merge = execute_string(repo_path, &["config", &format!("branch.{}.merge", old_name)])
+        .await
+        .ok();
+
+    let upstream = match (rename_remote, remote, merge) {
+        (false, _, _) => None,
+        (true, Some(remote), Some(merge)) => Some((remote, merge)),
+        // Refuse before touching anything, so a failure leaves the branch as it was
+        (true, _, _) => {
+            return Err(GitError {
+                message: format!("Branch {} has no upstream to rename", old_name),
+                command: "branch -m".to_string(),
+                exit_code: None,
+            });
+        }
+    };
+
+    ensure_new_branch_name(repo_path, new_name).await?;
+    execute_string(repo_path, &["branch", "-m", old_name, new_name]).await?;
+
+    let Some((remote, merge)) = upstream else {
+        return Ok(());
+    };
+    // The local rename has happened by now; say so when the remote part fails
+    let refspec = format!("{}:refs/heads/{}", new_name, new_name);
+    if let Err(e) = execute_string(repo_path, &["push", "-u", &remote, &refspec]).await {
+        return Err(GitError {
+            message: format!("Renamed the local branch to {}, but pushing it to {} failed: {}", new_name, remote, e.message),
+            ..e
+        });
+    }
+
+    let old_remote_branch = merge.trim_start_matches("refs/heads/");
+    if old_remote_branch != new_name {
+        if let Err(e) = delete_remote_branch(repo_path, &remote, old_remote_branch).await {
+            return Err(GitError {
+                message: format!(
+                    "Renamed the branch to {} locally and on {}, but deleting {}/{} failed: {}",
+                    new_name, remote, remote, old_remote_branch, e.message
+                ),
+                ..e
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Set the upstream of a branch, or remove it when `upstream` is `None`
+pub async fn set_branch_upstream(repo_path: &Path, branch: &str, upstream: Option<&str>) -> GitResult<()> {
+    ensure_writable(repo_path, "branch --set-upstream-to").await?;
+    match upstream {
+        Some(upstream) => {
+            let arg = format!("--set-upstream-to={}", upstream);
+            execute_string(repo_path, &["branch", &arg, branch]).await?;
+        }
+        None => {
+            execute_string(repo_path, &["branch", "--unset-upstream", branch]).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete a branch on a remote
+pub async fn delete_remote_branch(repo_path: &Path, remote: &str, branch: &str) -> GitResult<()> {
+    execute_string(repo_path, &["push", remote, "--delete", branch]).await?;
+    Ok(())
+}
+
+/// Find the default branch of a repository
+///
+/// Prefers the branch `origin/HEAD` points to, then falls back to a local `main`
+/// or `master`.
+pub async fn get_default_branch(repo_path: &Path) -> GitResult<Option<String>> {
+    if let Ok(head) = execute_string(repo_path, &["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"]).await {
+        if let Some(branch) = head.trim().strip_prefix("origin/") {
+            return Ok(Some(branch.to_string()));
+        }
+    }
+
+    for candidate in ["main", "master"] {
+        let refname = format!("refs/heads/{}", candidate);
+        if execute_string(repo_path, &["show-ref", "--verify", "--quiet", &refname]).await.is_ok() {
+            return Ok(Some(candidate.to_string()));
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,220 @@
+//! Git checkout module
+//!
+//! Switching branches with explicit handling of local changes that are in the way.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{ensure_writable, execute, execute_string, GitError, GitResult};
+
+/// What to do with local changes that a checkout would overwrite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutMode {
+    /// Refuse and report the files in the way
+    #[default]
+    Safe,
+    /// Stash all changes, including untracked files, then switch
+    Stash,
+    /// Carry the changes over with a three-way merge (`checkout --merge`)
+    Carry,
+    /// Throw the changes away (`checkout --force`)
+    Discard,
+}
+
+/// Outcome of a checkout
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckoutResult {
+    pub switched: bool,
+    /// Local branch now checked out, `None` for a detached HEAD
+    pub branch: Option<String>,
+    /// Files whose local changes blocked a safe checkout
+    pub blocked_files: Vec<String>,
+    /// Commit of the stash created by `CheckoutMode::Stash`
+    pub stash: Option<String>,
+    /// Files left with conflicts after carrying changes over
+    pub conflicts: Vec<String>,
+}
+
+/// What a name given to checkout refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CheckoutTarget {
+    Local(String),
+    /// A remote-tracking branch and the local branch that should track it
+    Remote { remote_ref: String, local: String },
+    Detached(String),
+}
+
+async fn ref_exists(path: &Path, refname: &str) -> bool {
+    execute_string(path, &["show-ref", "--verify", "--quiet", refname]).await.is_ok()
+}
+
+/// Resolve a branch name, remote-tracking branch or commit-ish through the ref store
+async fn resolve_target(path: &Path, name: &str) -> GitResult<CheckoutTarget> {
+    if ref_exists(path, &format!("refs/heads/{}", name)).await {
+        return Ok(CheckoutTarget::Local(name.to_string()));
+    }
+
+    // `origin/feature`: strip the remote whose refs/remotes namespace contains it
+    if ref_exists(path, &format!("refs/remotes/{}", name)).await {
+        let remotes = execute_string(path, &["remote"]).await.unwrap_or_default();
+        let local = remotes
+            .lines()
+            .filter_map(|remote| name.strip_prefix(&format!("{}/", remote)))
+            // Remote names may contain slashes; the longest match is the remote
+            .min_by_key(|rest| rest.len())
+            .unwrap_or(name);
+        return Ok(CheckoutTarget::Remote {
+            remote_ref: name.to_string(),
+            local: local.to_string(),
+        });
+    }
+
+    // `feature` that only exists on one remote, as `git checkout` itself would guess
+    let pattern = format!("refs/remotes/*/{}", name);
+    let candidates = execute_string(path, &["for-each-ref", "--format=%(refname:short)", &pattern])
+        .await
+        .unwrap_or_default();
+    let candidates: Vec<&str> = candidates.lines().collect();
+    if let [remote_ref] = candidates.as_slice() {
+        return Ok(CheckoutTarget::Remote {
+            remote_ref: remote_ref.to_string(),
+            local: name.to_string(),
+        });
+    }
+
+    let commit = format!("{}^{{commit}}", name);
+    match execute_string(path, &["rev-parse", "--verify", "--quiet", &commit]).await {
+        Ok(hash) => Ok(CheckoutTarget::Detached(hash.trim().to_string())),
+        Err(_) => Err(GitError {
+            message: if candidates.len() > 1 {
+                format!("'{}' matches branches on several remotes: {}", name, candidates.join(", "))
+            } else {
+                format!("No branch or commit named '{}'", name)
+            },
+            command: format!("checkout {}", name),
+            exit_code: None,
+        }),
+    }
+}
+
+/// Files listed by git as being in the way of a checkout
+fn parse_blocked_files(stderr: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut in_list = false;
+
+    for line in stderr.lines() {
+        if line.contains("would be overwritten by") {
+            // Single-file form: Untracked working tree file 'u' would be overwritten by merge.
+            if let Some((_, rest)) = line.split_once('\'') {
+                if let Some((file, _)) = rest.split_once('\'') {
+                    files.push(file.to_string());
+                }
+            }
+            in_list = line.trim_end().ends_with(':');
+        } else if in_list && line.starts_with('\t') {
+            files.push(line.trim().to_string());
+        } else {
+            in_list = false;
+        }
+    }
+
+    files
+}
+
+async fn conflicted_files(path: &Path) -> Vec<String> {
+    execute_string(path, &["diff", "--name-only", "--diff-filter=U"])
+        .await
+        .map(|out| out.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Checkout a branch, remote-tracking branch or commit, handling local changes per `mode`
+///
+/// A safe checkout blocked by local changes is not an error: the result lists the files
+/// in the way so the caller can retry with another mode.
+pub async fn checkout_with(path: &Path, name: &str, mode: CheckoutMode) -> GitResult<CheckoutResult> {
+    ensure_writable(path, "checkout").await?;
+    let target = resolve_target(path, name).await?;
+
+    let mut args: Vec<String> = vec!["checkout".to_string()];
+    match mode {
+        CheckoutMode::Carry => args.push("--merge".to_string()),
+        CheckoutMode::Discard => args.push("--force".to_string()),
+        CheckoutMode::Safe | CheckoutMode::Stash => {}
+    }
+    let branch = match &target {
+        CheckoutTarget::Local(local) => {
+            args.push(local.clone());
+            Some(local.clone())
+        }
+        CheckoutTarget::Remote { remote_ref, local } => {
+            // Reuse an existing local branch of the same name
+            if !ref_exists(path, &format!("refs/heads/{}", local)).await {
+                args.extend(["-b".to_string(), local.clone(), "--track".to_string()]);
+                args.push(remote_ref.clone());
+            } else {
+                args.push(local.clone());
+            }
+            Some(local.clone())
+        }
+        CheckoutTarget::Detached(hash) => {
+            args.extend(["--detach".to_string(), hash.clone()]);
+            None
+        }
+    };
+    // Never let a branch name be taken for a path
+    args.push("--".to_string());
+
+    let mut stash = None;
+    if mode == CheckoutMode::Stash {
+        let message = format!("pinax: local changes before switching to {}", name);
+        let before = execute_string(path, &["rev-parse", "--verify", "--quiet", "refs/stash"]).await.ok();
+        execute_string(path, &["stash", "push", "--include-untracked", "-m", &message]).await?;
+        let after = execute_string(path, &["rev-parse", "--verify", "--quiet", "refs/stash"]).await.ok();
+        // Nothing to stash leaves refs/stash untouched
+        if after.is_some() && after != before {
+            stash = after.map(|h| h.trim().to_string());
+        }
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = execute(path, &arg_refs).await?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if stash.is_some() {
+            // Put the changes back where they were
+            let _ = execute(path, &["stash", "pop", "--index"]).await;
+        }
+
+        let blocked_files = parse_blocked_files(&stderr);
+        if mode == CheckoutMode::Safe && !blocked_files.is_empty() {
+            return Ok(CheckoutResult {
+                blocked_files,
+                ..Default::default()
+            });
+        }
+
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: args.join(" "),
+            exit_code: output.status.code(),
+        });
+    }
+
+    let conflicts = match mode {
+        CheckoutMode::Carry => conflicted_files(path).await,
+        _ => Vec::new(),
+    };
+
+    Ok(CheckoutResult {
+        switched: true,
+        branch,
+        blocked_files: Vec::new(),
+        stash,
+        conflicts,
+    })
+}
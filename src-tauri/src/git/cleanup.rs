@@ -0,0 +1,310 @@
+//! Stale branch cleanup
+//!
+//! Finds local branches that are safe to delete and deletes them in batches. Every
+//! deleted tip is recorded in the reflog of a private ref, so deletions can be undone
+//! and the commits survive garbage collection until the reflog expires.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::branches::{get_default_branch, list_branches};
+use super::executor::{ensure_writable, execute, execute_string, execute_with_input, GitError, GitResult};
+
+/// Private ref whose reflog records deleted branch tips
+pub const DELETED_BRANCHES_REF: &str = "refs/pinax/deleted-branches";
+
+/// Why a branch is considered stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    /// The upstream branch was deleted on the remote
+    UpstreamGone,
+    /// The tip is reachable from the default branch
+    Merged,
+    /// Its changes reached the default branch through a squash or rebase merge
+    SquashMerged,
+    /// No commits for longer than the requested number of days
+    Inactive,
+}
+
+/// A local branch that is a candidate for deletion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBranch {
+    pub name: String,
+    pub tip: String,
+    pub upstream: Option<String>,
+    pub last_commit_date: Option<String>,
+    pub days_inactive: u64,
+    pub reasons: Vec<StaleReason>,
+}
+
+/// Result of deleting one branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedBranch {
+    pub name: String,
+    pub tip: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A deletion that can be restored from the reflog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedBranchRecord {
+    pub name: String,
+    pub tip: String,
+    pub remote: Option<String>,
+    /// Upstream branch ref on `remote`, e.g. `refs/heads/feature`
+    pub merge: Option<String>,
+    /// Unix timestamp of the deletion
+    pub deleted_at: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Whether the changes of `branch` already exist on `target` under other commits
+///
+/// Rebase merges are caught by `git cherry` matching every commit by patch-id. Squash
+/// merges are caught by comparing the patch-id of the whole branch diff against the
+/// patch-ids of the commits on `target` since the merge base. Nothing is written to
+/// the repository.
+async fn is_squash_merged(repo_path: &Path, target: &str, branch: &str) -> bool {
+    if let Ok(output) = execute_string(repo_path, &["cherry", target, branch]).await {
+        let lines: Vec<&str> = output.lines().collect();
+        if !lines.is_empty() && lines.iter().all(|l| l.starts_with('-')) {
+            return true;
+        }
+    }
+
+    let Ok(base) = execute_string(repo_path, &["merge-base", target, branch]).await else {
+        return false;
+    };
+    let base = base.trim();
+
+    let Ok(diff) = execute_string(repo_path, &["diff", "--no-color", "--no-ext-diff", base, branch]).await else {
+        return false;
+    };
+    if diff.is_empty() {
+        return false;
+    }
+    let Some(branch_id) = patch_ids(repo_path, &format!("{}\n", diff)).await.into_iter().next() else {
+        return false;
+    };
+
+    let range = format!("{}..{}", base, target);
+    let Ok(log) = execute_string(repo_path, &["log", "-p", "--no-color", "--no-ext-diff", "--format=commit %H", &range, "--"]).await
+    else {
+        return false;
+    };
+    patch_ids(repo_path, &format!("{}\n", log)).await.contains(&branch_id)
+}
+
+/// Stable patch-ids of the patches in `patches`, in order
+async fn patch_ids(repo_path: &Path, patches: &str) -> Vec<String> {
+    execute_with_input(repo_path, &["patch-id", "--stable"], patches)
+        .await
+        .map(|out| out.lines().filter_map(|l| l.split_whitespace().next()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Find local branches that are gone upstream, merged, squash-merged or inactive
+///
+/// `inactive_days` enables the inactivity check. The current and default branches
+/// are never reported.
+pub async fn find_stale_branches(repo_path: &Path, inactive_days: Option<u32>) -> GitResult<Vec<StaleBranch>> {
+    let default = get_default_branch(repo_path).await?;
+    // Compare against the remote default when it exists, since the local one may lag behind
+    let target = match &default {
+        Some(name) => {
+            let remote = format!("refs/remotes/origin/{}", name);
+            if execute_string(repo_path, &["show-ref", "--verify", "--quiet", &remote]).await.is_ok() {
+                Some(remote)
+            } else {
+                Some(format!("refs/heads/{}", name))
+            }
+        }
+        None => None,
+    };
+
+    let tips = execute_string(
+        repo_path,
+        &["for-each-ref", "--format=%(refname:short)\t%(objectname)\t%(committerdate:unix)", "refs/heads"],
+    )
+    .await?;
+    let now = now_secs();
+
+    let mut stale = Vec::new();
+    for branch in list_branches(repo_path).await? {
+        if branch.is_remote || branch.is_current || default.as_deref() == Some(branch.name.as_str()) {
+            continue;
+        }
+
+        let Some((tip, committed)) = tips.lines().find_map(|l| {
+            let mut parts = l.split('\t');
+            (parts.next() == Some(branch.name.as_str()))
+                .then(|| (parts.next().unwrap_or("").to_string(), parts.next().and_then(|t| t.parse::<u64>().ok())))
+        }) else {
+            continue;
+        };
+        let days_inactive = committed.map(|t| now.saturating_sub(t) / 86_400).unwrap_or(0);
+
+        let mut reasons = Vec::new();
+        if branch.upstream_gone {
+            reasons.push(StaleReason::UpstreamGone);
+        }
+        if branch.merged {
+            reasons.push(StaleReason::Merged);
+        } else if let Some(target) = &target {
+            if is_squash_merged(repo_path, target, &tip).await {
+                reasons.push(StaleReason::SquashMerged);
+            }
+        }
+        if inactive_days.is_some_and(|days| days_inactive >= u64::from(days)) {
+            reasons.push(StaleReason::Inactive);
+        }
+
+        if !reasons.is_empty() {
+            stale.push(StaleBranch {
+                name: branch.name,
+                tip,
+                upstream: branch.upstream,
+                last_commit_date: branch.last_commit_date,
+                days_inactive,
+                reasons,
+            });
+        }
+    }
+
+    Ok(stale)
+}
+
+/// Upstream config of a branch as `(remote, merge ref)`, even when the upstream is gone
+async fn branch_upstream(repo_path: &Path, name: &str) -> Option<(String, String)> {
+    let remote = execute_string(repo_path, &["config", &format!("branch.{}.remote", name)]).await.ok()?;
+    let merge = execute_string(repo_path, &["config", &format!("branch.{}.merge", name)]).await.ok()?;
+    Some((remote.trim().to_string(), merge.trim().to_string()))
+}
+
+/// Record a branch tip in the deleted-branches reflog
+async fn record_deletion(repo_path: &Path, name: &str, tip: &str, upstream: Option<&(String, String)>) -> GitResult<()> {
+    ensure_writable(repo_path, "branch -D").await?;
+    let message = match upstream {
+        Some((remote, merge)) => format!("deleted {} upstream {} {}", name, remote, merge),
+        None => format!("deleted {}", name),
+    };
+    execute_string(repo_path, &["update-ref", "--create-reflog", "-m", &message, DELETED_BRANCHES_REF, tip]).await?;
+    Ok(())
+}
+
+/// Delete several local branches, recording each tip so it can be restored
+///
+/// Branches are force-deleted, because squash-merged branches are never "merged" to
+/// git. Failures are reported per branch and do not stop the batch.
+pub async fn delete_branches(repo_path: &Path, names: &[String]) -> Vec<DeletedBranch> {
+    let mut results = Vec::new();
+
+    for name in names {
+        let refname = format!("refs/heads/{}", name);
+        let tip = match execute_string(repo_path, &["rev-parse", "--verify", &refname]).await {
+            Ok(tip) => tip.trim().to_string(),
+            Err(e) => {
+                results.push(DeletedBranch {
+                    name: name.clone(),
+                    tip: None,
+                    error: Some(e.message),
+                });
+                continue;
+            }
+        };
+        let upstream = branch_upstream(repo_path, name).await;
+
+        // Never delete without a restore point
+        let result = match record_deletion(repo_path, name, &tip, upstream.as_ref()).await {
+            Ok(()) => execute_string(repo_path, &["branch", "-D", name]).await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+
+        results.push(DeletedBranch {
+            name: name.clone(),
+            tip: Some(tip),
+            error: result.err().map(|e| e.message),
+        });
+    }
+
+    results
+}
+
+/// List deleted branches that can be restored, newest first
+pub async fn list_deleted_branches(repo_path: &Path) -> GitResult<Vec<DeletedBranchRecord>> {
+    let output = execute(
+        repo_path,
+        &["reflog", "show", "--date=unix", "--format=%H%x09%gd%x09%gs", DELETED_BRANCHES_REF],
+    )
+    .await?;
+    // No branch was ever deleted
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut records = Vec::new();
+    for line in stdout.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(tip), Some(selector), Some(subject)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let Some(rest) = subject.strip_prefix("deleted ") else { continue };
+        let (name, remote, merge) = match rest.split_once(" upstream ").and_then(|(name, upstream)| {
+            let (remote, merge) = upstream.split_once(' ')?;
+            Some((name, remote, merge))
+        }) {
+            Some((name, remote, merge)) => (name, Some(remote.to_string()), Some(merge.to_string())),
+            None => (rest, None, None),
+        };
+        // The selector looks like refs/pinax/deleted-branches@{1700000000}
+        let deleted_at = selector
+            .rsplit_once("@{")
+            .and_then(|(_, t)| t.trim_end_matches('}').parse().ok())
+            .unwrap_or(0);
+
+        records.push(DeletedBranchRecord {
+            name: name.to_string(),
+            tip: tip.to_string(),
+            remote,
+            merge,
+            deleted_at,
+        });
+    }
+
+    Ok(records)
+}
+
+/// Recreate a deleted branch at its recorded tip
+///
+/// `tip` selects a specific deletion when the same name was deleted more than once;
+/// otherwise the most recent one is restored, along with its upstream config.
+pub async fn restore_branch(repo_path: &Path, name: &str, tip: Option<&str>) -> GitResult<()> {
+    ensure_writable(repo_path, "branch").await?;
+    let records = list_deleted_branches(repo_path).await?;
+    let record = records
+        .iter()
+        .find(|r| r.name == name && tip.is_none_or(|t| r.tip.starts_with(t)))
+        .ok_or_else(|| GitError {
+            message: format!("No deleted branch named {} to restore", name),
+            command: "reflog show".to_string(),
+            exit_code: None,
+        })?;
+
+    execute_string(repo_path, &["branch", name, &record.tip]).await?;
+
+    if let (Some(remote), Some(merge)) = (&record.remote, &record.merge) {
+        execute_string(repo_path, &["config", &format!("branch.{}.remote", name), remote]).await?;
+        execute_string(repo_path, &["config", &format!("branch.{}.merge", name), merge]).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Git compare module
+//!
+//! Compares two refs: commits unique to each side, the changes since their merge base,
+//! and whether merging them would conflict.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute, execute_string, GitError, GitResult};
+use super::history::{parse_log_line, parse_name_status_z, LOG_FORMAT};
+use super::types::{CommitInfo, FileChange};
+
+/// Commits listed per side unless a limit is given
+const DEFAULT_COMMIT_LIMIT: usize = 500;
+
+/// Comparison of two refs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareResult {
+    pub base: String,
+    pub target: String,
+    pub merge_base: Option<String>,
+    /// Commits reachable only from `base`
+    pub base_only: Vec<CommitInfo>,
+    /// Commits reachable only from `target`
+    pub target_only: Vec<CommitInfo>,
+    /// Files changed on `target` since the merge base (`git diff base...target`)
+    pub files: Vec<FileChange>,
+}
+
+/// Result of a merge dry run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergePreview {
+    pub clean: bool,
+    /// Tree the merge would produce; written to the object store but not referenced
+    pub tree: Option<String>,
+    pub conflicts: Vec<String>,
+    /// Informational messages, e.g. `CONFLICT (content): Merge conflict in a.txt`
+    pub messages: Vec<String>,
+}
+
+/// Compare `base` (usually `HEAD`) with `target`
+pub async fn compare_refs(repo_path: &Path, base: &str, target: &str, limit: Option<usize>) -> GitResult<CompareResult> {
+    let range = format!("{}...{}", base, target);
+    let limit = limit.unwrap_or(DEFAULT_COMMIT_LIMIT);
+
+    let merge_base = execute_string(repo_path, &["merge-base", base, target])
+        .await
+        .ok()
+        .map(|h| h.trim().to_string());
+
+    let base_only = unique_commits(repo_path, target, base, limit).await?;
+    let target_only = unique_commits(repo_path, base, target, limit).await?;
+
+    // Unrelated histories have no merge base and nothing to diff against
+    let files = match merge_base {
+        Some(_) => parse_name_status_z(&execute_string(repo_path, &["diff", "-z", "--name-status", "-M", &range, "--"]).await?),
+        None => Vec::new(),
+    };
+
+    Ok(CompareResult {
+        base: base.to_string(),
+        target: target.to_string(),
+        merge_base,
+        base_only,
+        target_only,
+        files,
+    })
+}
+
+/// Up to `limit` commits reachable from `tip` but not from `exclude`, newest first
+async fn unique_commits(repo_path: &Path, exclude: &str, tip: &str, limit: usize) -> GitResult<Vec<CommitInfo>> {
+    let range = format!("{}..{}", exclude, tip);
+    let output = execute_string(
+        repo_path,
+        &[
+            "log",
+            &format!("--max-count={}", limit),
+            "--date=iso-strict",
+            &format!("--format={}", LOG_FORMAT),
+            &range,
+            "--",
+        ],
+    )
+    .await?;
+
+    Ok(output.lines().filter_map(parse_log_line).collect())
+}
+
+/// Diff of one file on `target` since its merge base with `base`
+pub async fn get_compare_file_diff(repo_path: &Path, base: &str, target: &str, file_path: &str) -> GitResult<String> {
+    let range = format!("{}...{}", base, target);
+    execute_string(repo_path, &["diff", "--no-color", &range, "--", file_path]).await
+}
+
+/// Check whether merging `target` into `base` would conflict, without touching the
+/// working tree or index
+///
+/// Requires git 2.38 or newer for `merge-tree --write-tree`.
+pub async fn preview_merge(repo_path: &Path, base: &str, target: &str) -> GitResult<MergePreview> {
+    let args = ["merge-tree", "--write-tree", "--name-only", base, target];
+    let output = execute(repo_path, &args).await?;
+
+    // Exit code 1 means the merge has conflicts; anything else is a real failure
+    let code = output.status.code();
+    if !output.status.success() && code != Some(1) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.contains("--write-tree") || stderr.contains("usage: git merge-tree") {
+            "Merge preview requires git 2.38 or newer".to_string()
+        } else {
+            stderr.to_string()
+        };
+        return Err(GitError {
+            message,
+            command: args.join(" "),
+            exit_code: code,
+        });
+    }
+
+    // Tree id, then conflicted paths, then a blank line and the messages
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let tree = lines.next().map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+
+    let mut conflicts: Vec<String> = Vec::new();
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        if !conflicts.iter().any(|c| c == line) {
+            conflicts.push(line.to_string());
+        }
+    }
+    let messages = lines.filter(|l| !l.is_empty()).map(|l| l.to_string()).collect();
+
+    Ok(MergePreview {
+        clean: output.status.success(),
+        tree,
+        conflicts,
+        messages,
+    })
+}
@@ -0,0 +1,242 @@
+//! Git config module
+//!
+//! Read and write git configuration at any scope, with the origin of each value.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute, execute_string, GitError, GitResult};
+
+/// Configuration scope, as reported by `--show-scope`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigScope {
+    System,
+    Global,
+    Local,
+    Worktree,
+    Command,
+    Unknown,
+}
+
+impl ConfigScope {
+    fn parse(scope: &str) -> Self {
+        match scope {
+            "system" => ConfigScope::System,
+            "global" => ConfigScope::Global,
+            "local" => ConfigScope::Local,
+            "worktree" => ConfigScope::Worktree,
+            "command" => ConfigScope::Command,
+            _ => ConfigScope::Unknown,
+        }
+    }
+
+    /// The `git config` flag selecting this scope for writes
+    fn flag(&self) -> GitResult<&'static str> {
+        match self {
+            ConfigScope::System => Ok("--system"),
+            ConfigScope::Global => Ok("--global"),
+            ConfigScope::Local => Ok("--local"),
+            ConfigScope::Worktree => Ok("--worktree"),
+            _ => Err(GitError {
+                message: "Config values can only be written to system, global, local or worktree scope".to_string(),
+                command: "config".to_string(),
+                exit_code: None,
+            }),
+        }
+    }
+}
+
+/// A config value together with where it was defined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: String,
+    pub scope: ConfigScope,
+    /// Origin reported by `--show-origin`, e.g. `file:.git/config`
+    pub origin: String,
+}
+
+/// The identity git would use for commits in a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveIdentity {
+    pub name: Option<ConfigEntry>,
+    pub email: Option<ConfigEntry>,
+    pub signing_key: Option<ConfigEntry>,
+}
+
+/// A conditional include, i.e. `includeIf.<condition>.path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncludeIf {
+    pub condition: String,
+    pub path: String,
+    pub scope: ConfigScope,
+    pub origin: String,
+}
+
+/// Parse `git config -z --show-scope --show-origin --list` output
+fn parse_entries(output: &str) -> Vec<ConfigEntry> {
+    let fields: Vec<&str> = output.split('\0').collect();
+    let mut entries = Vec::new();
+
+    for chunk in fields.chunks(3) {
+        if chunk.len() < 3 || chunk[2].is_empty() {
+            continue;
+        }
+        // Keys without a value (implicit `true`) have no newline separator
+        let (key, value) = chunk[2].split_once('\n').unwrap_or((chunk[2], "true"));
+        entries.push(ConfigEntry {
+            key: key.to_string(),
+            value: value.to_string(),
+            scope: ConfigScope::parse(chunk[0]),
+            origin: chunk[1].to_string(),
+        });
+    }
+
+    entries
+}
+
+/// List every config value visible from a repository, lowest precedence first
+pub async fn list_config(repo_path: &Path, scope: Option<ConfigScope>) -> GitResult<Vec<ConfigEntry>> {
+    let mut args = vec!["config", "-z", "--show-scope", "--show-origin", "--list"];
+    if let Some(scope) = scope {
+        args.insert(1, scope.flag()?);
+    }
+
+    let output = execute(repo_path, &args).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // A missing config file at the requested scope is not an error
+        if stderr.contains("unable to read config file") {
+            return Ok(Vec::new());
+        }
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: "config --list".to_string(),
+            exit_code: output.status.code(),
+        });
+    }
+
+    Ok(parse_entries(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Get the effective value of a key and where it comes from
+pub async fn get_config_entry(repo_path: &Path, key: &str) -> GitResult<Option<ConfigEntry>> {
+    let output = execute(repo_path, &["config", "-z", "--show-scope", "--show-origin", "--get", key]).await?;
+
+    // Exit code 1 means the key is not set
+    if output.status.code() == Some(1) {
+        return Ok(None);
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: format!("config --get {}", key),
+            exit_code: output.status.code(),
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+    if fields.len() < 3 {
+        return Ok(None);
+    }
+
+    Ok(Some(ConfigEntry {
+        key: key.to_string(),
+        value: fields[2].to_string(),
+        scope: ConfigScope::parse(fields[0]),
+        origin: fields[1].to_string(),
+    }))
+}
+
+/// Set a config value at the given scope
+pub async fn set_config(repo_path: &Path, key: &str, value: &str, scope: ConfigScope) -> GitResult<()> {
+    let flag = scope.flag()?;
+    execute_string(repo_path, &["config", flag, key, value]).await?;
+    Ok(())
+}
+
+/// Remove a config value at the given scope
+pub async fn unset_config(repo_path: &Path, key: &str, scope: ConfigScope) -> GitResult<()> {
+    let flag = scope.flag()?;
+    let output = execute(repo_path, &["config", flag, "--unset-all", key]).await?;
+
+    // Exit code 5 means the key was not set, which is what we want anyway
+    if !output.status.success() && output.status.code() != Some(5) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: format!("config {} --unset-all {}", flag, key),
+            exit_code: output.status.code(),
+        });
+    }
+    Ok(())
+}
+
+/// Get the identity used for commits in a repository, with the origin of each value
+pub async fn get_effective_identity(repo_path: &Path) -> GitResult<EffectiveIdentity> {
+    Ok(EffectiveIdentity {
+        name: get_config_entry(repo_path, "user.name").await?,
+        email: get_config_entry(repo_path, "user.email").await?,
+        signing_key: get_config_entry(repo_path, "user.signingkey").await?,
+    })
+}
+
+/// List the conditional includes visible from a repository
+pub async fn list_include_ifs(repo_path: &Path) -> GitResult<Vec<IncludeIf>> {
+    let entries = list_config(repo_path, None).await?;
+
+    Ok(entries
+        .into_iter()
+        .filter_map(|e| {
+            // Keys look like `includeif.gitdir:~/work/.path`; the condition may contain dots
+            let rest = e.key.strip_prefix("includeif.")?;
+            let condition = rest.strip_suffix(".path")?;
+            Some(IncludeIf {
+                condition: condition.to_string(),
+                path: e.value,
+                scope: e.scope,
+                origin: e.origin,
+            })
+        })
+        .collect())
+}
+
+/// Bind an identity to every repository under `directory` through `includeIf`
+///
+/// The identity is written to its own file, which the global config includes for
+/// `gitdir:<directory>/`. Repositories elsewhere never see these values.
+pub async fn set_include_if_identity(
+    directory: &Path,
+    include_file: &Path,
+    name: &str,
+    email: &str,
+    signing_key: Option<&str>,
+) -> GitResult<()> {
+    let file = include_file.to_string_lossy().to_string();
+    let cwd = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+
+    execute_string(&cwd, &["config", "--file", &file, "user.name", name]).await?;
+    execute_string(&cwd, &["config", "--file", &file, "user.email", email]).await?;
+    match signing_key {
+        Some(key) => {
+            execute_string(&cwd, &["config", "--file", &file, "user.signingkey", key]).await?;
+        }
+        None => {
+            let _ = execute(&cwd, &["config", "--file", &file, "--unset-all", "user.signingkey"]).await;
+        }
+    }
+
+    // gitdir conditions match by prefix only when they end with a slash
+    let mut dir = directory.to_string_lossy().replace('\\', "/");
+    if !dir.ends_with('/') {
+        dir.push('/');
+    }
+    let key = format!("includeIf.gitdir:{}.path", dir);
+    execute_string(&cwd, &["config", "--global", "--replace-all", &key, &file]).await?;
+
+    Ok(())
+}
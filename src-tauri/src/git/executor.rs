@@ -3,10 +3,12 @@
 //! Low-level module for executing Git commands via the command line.
 //! All Git operations go through this module for consistent error handling.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Output;
 use tokio::process::Command;
 
+use super::types::RepositoryKind;
+
 /// Result type for Git operations
 pub type GitResult<T> = Result<T, GitError>;
 
@@ -75,6 +77,140 @@ pub async fn execute_string(repo_path: &Path, args: &[&str]) -> GitResult<String
     Ok(stdout.trim_end().to_string())
 }
 
+/// Execute a Git command with `input` written to its stdin and return stdout as a String
+///
+/// Used for the `--batch` and `--stdin` modes that take one item per line.
+pub async fn execute_with_input(repo_path: &Path, args: &[&str], input: &str) -> GitResult<String> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let mut command = Command::new("git");
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000);
+
+    let spawn_error = |e: std::io::Error| GitError {
+        message: format!("Failed to execute git: {}", e),
+        command: args.join(" "),
+        exit_code: None,
+    };
+
+    let mut child = command
+        .args(args)
+        .current_dir(repo_path)
+        .envs(std::env::vars())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Write from a separate task so a large input cannot deadlock against a full stdout pipe
+    let mut stdin = child.stdin.take();
+    let input = input.to_string();
+    let writer = tokio::spawn(async move {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+    });
+
+    let output = child.wait_with_output().await.map_err(spawn_error)?;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: args.join(" "),
+            exit_code: output.status.code(),
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.trim_end().to_string())
+}
+
+/// How often a streamed command checks whether it should stop while git is silent
+const STOP_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Execute a Git command, passing each line of stdout to `on_line` as it arrives
+///
+/// Returning `false` from `on_line` stops reading and kills the process, which is
+/// not treated as an error. The process is also killed as soon as `should_stop`
+/// returns `true`, even while git produces no output (e.g. a pickaxe search).
+pub async fn execute_lines_until<S, F>(repo_path: &Path, args: &[&str], should_stop: S, mut on_line: F) -> GitResult<()>
+where
+    S: Fn() -> bool,
+    F: FnMut(&str) -> bool,
+{
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+    let mut command = Command::new("git");
+    #[cfg(target_os = "windows")]
+    command.creation_flags(0x08000000);
+
+    let spawn_error = |e: std::io::Error| GitError {
+        message: format!("Failed to execute git: {}", e),
+        command: args.join(" "),
+        exit_code: None,
+    };
+
+    let mut child = command
+        .args(args)
+        .current_dir(repo_path)
+        .envs(std::env::vars())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Drain stderr concurrently so a chatty command cannot block on a full pipe
+    let mut stderr = child.stderr.take();
+    let stderr_task = tokio::spawn(async move {
+        let mut buf = String::new();
+        if let Some(stderr) = stderr.as_mut() {
+            let _ = stderr.read_to_string(&mut buf).await;
+        }
+        buf
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        loop {
+            if should_stop() {
+                let _ = child.kill().await;
+                return Ok(());
+            }
+            // `next_line` is cancel safe, so timing out loses no data
+            let Ok(next) = tokio::time::timeout(STOP_POLL_INTERVAL, lines.next_line()).await else {
+                continue;
+            };
+            let Some(line) = next.map_err(spawn_error)? else { break };
+            if !on_line(&line) {
+                let _ = child.kill().await;
+                return Ok(());
+            }
+        }
+    }
+
+    let status = child.wait().await.map_err(spawn_error)?;
+    if !status.success() {
+        return Err(GitError {
+            message: stderr_task.await.unwrap_or_default(),
+            command: args.join(" "),
+            exit_code: status.code(),
+        });
+    }
+
+    Ok(())
+}
+
 /// Execute a global Git command and return stdout as a String
 pub async fn execute_global_string(args: &[&str]) -> GitResult<String> {
     let mut command = Command::new("git");
@@ -107,8 +243,98 @@ pub async fn execute_global_string(args: &[&str]) -> GitResult<String> {
     Ok(stdout.trim_end().to_string())
 }
 
-/// Check if a directory is a Git repository
+/// Check if a directory is the root of a Git repository
 pub async fn is_git_repo(path: &Path) -> bool {
-    let git_dir = path.join(".git");
-    git_dir.exists()
+    detect_repository_kind(path).await.is_some()
+}
+
+/// Cheap filesystem check that rules out most directories without spawning git
+fn looks_like_repository(path: &Path) -> bool {
+    // `.git` is a directory for normal checkouts and a file for worktrees and submodules
+    if path.join(".git").exists() {
+        return true;
+    }
+    // Bare repositories keep their internals at the top level
+    path.join("HEAD").is_file() && path.join("objects").is_dir() && path.join("refs").is_dir()
+}
+
+/// Determine what kind of repository `path` is the root of, if any
+///
+/// Uses `git rev-parse --is-bare-repository --is-inside-work-tree --git-dir --git-common-dir`
+/// so that `.git` files, bare repositories and nested directories are classified the way
+/// git itself sees them.
+pub async fn detect_repository_kind(path: &Path) -> Option<RepositoryKind> {
+    if !looks_like_repository(path) {
+        return None;
+    }
+
+    let output = execute_string(
+        path,
+        &["rev-parse", "--is-bare-repository", "--is-inside-work-tree", "--git-dir", "--git-common-dir"],
+    )
+    .await
+    .ok()?;
+
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() < 4 {
+        return None;
+    }
+    let is_bare = lines[0] == "true";
+    let in_work_tree = lines[1] == "true";
+    let git_dir = path.join(lines[2]);
+    let common_dir = path.join(lines[3]);
+
+    if is_bare {
+        // Only the top of a bare repository counts, not its refs/ or objects/ directories
+        return if lines[2] == "." { Some(RepositoryKind::Bare) } else { None };
+    }
+    if !in_work_tree {
+        return None;
+    }
+
+    // The prefix line is empty at the top level; the superproject line only exists for submodules
+    let output = execute(path, &["rev-parse", "--show-prefix", "--show-superproject-working-tree"]).await.ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    if !lines.next().unwrap_or_default().is_empty() {
+        return None;
+    }
+    let superproject = lines.next().unwrap_or_default();
+
+    if !superproject.is_empty() {
+        Some(RepositoryKind::Submodule)
+    } else if git_dir != common_dir {
+        Some(RepositoryKind::Worktree)
+    } else {
+        Some(RepositoryKind::Normal)
+    }
+}
+
+/// Check whether a repository has no working tree
+pub async fn is_bare_repository(path: &Path) -> bool {
+    execute_string(path, &["rev-parse", "--is-bare-repository"])
+        .await
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Fail for bare repositories, which are only browsed read-only
+///
+/// Called by every operation that changes the working tree, index or local refs.
+/// Fetching stays allowed so that browsed history can be kept up to date.
+pub async fn ensure_writable(path: &Path, command: &str) -> GitResult<()> {
+    if is_bare_repository(path).await {
+        return Err(GitError {
+            message: "Bare repositories are read-only".to_string(),
+            command: command.to_string(),
+            exit_code: None,
+        });
+    }
+    Ok(())
+}
+
+/// Resolve the git directory of a repository, which is not always `<path>/.git`
+pub async fn git_dir(path: &Path) -> GitResult<PathBuf> {
+    let dir = execute_string(path, &["rev-parse", "--git-dir"]).await?;
+    Ok(path.join(dir.trim()))
 }
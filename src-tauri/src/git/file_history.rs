@@ -0,0 +1,167 @@
+//! File history module
+//!
+//! History of a single file across renames, and file contents at any revision.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute, execute_string, GitError, GitResult};
+use super::history::{parse_log_line, parse_name_status_z, LOG_FORMAT};
+use super::types::{CommitInfo, FileStatus};
+
+/// Commits returned per page unless a limit is given
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Files larger than this are reported without content
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Separates commits in the log output, since the name-status block follows the header
+const RECORD_MARKER: char = '\u{1e}';
+
+/// One commit that changed a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHistoryEntry {
+    #[serde(flatten)]
+    pub commit: CommitInfo,
+    /// Path of the file in this commit
+    pub path: String,
+    /// Previous path when this commit renamed or copied the file
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    /// Diff of the file in this commit, when requested
+    pub diff: Option<String>,
+}
+
+/// Contents of a file at a revision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileAtRevision {
+    pub revision: String,
+    pub path: String,
+    pub size: u64,
+    pub is_binary: bool,
+    /// `None` for binary files and files over the size limit
+    pub content: Option<String>,
+}
+
+/// Get the commits that changed a file, following renames
+///
+/// Merges are diffed against their first parent, so every commit git lists has an
+/// entry. `skip` is applied to the entries rather than passed to git, whose `--skip`
+/// also skips the rename detection of `--follow`.
+pub async fn get_file_history(
+    repo_path: &Path,
+    file_path: &str,
+    skip: usize,
+    limit: Option<usize>,
+    include_diff: bool,
+) -> GitResult<Vec<FileHistoryEntry>> {
+    // Signatures are verified on demand for the rows shown
+    let format = format!("--format={}{}", RECORD_MARKER, LOG_FORMAT);
+    let count_arg = format!("--max-count={}", skip + limit.unwrap_or(DEFAULT_PAGE_SIZE));
+    let output = execute_string(
+        repo_path,
+        &[
+            "log", "-z", "--follow", "--name-status", "-M", "--diff-merges=first-parent", "--date=iso-strict",
+            &format, &count_arg, "--", file_path,
+        ],
+    )
+    .await?;
+
+    // Path of the file in the newest commit not seen yet
+    let mut current = file_path.to_string();
+    let mut entries = Vec::new();
+    for record in output.split(RECORD_MARKER).filter(|r| !r.trim().is_empty()) {
+        // The header ends with NUL and a newline, then come the NUL-separated changes
+        let (header, changes) = record.split_once('\0').unwrap_or((record, ""));
+        let Some(commit) = parse_log_line(header) else { continue };
+
+        // With --follow only the followed file is listed
+        let entry = match parse_name_status_z(changes.trim_start_matches('\n')).into_iter().next() {
+            Some(change) => FileHistoryEntry {
+                commit,
+                path: change.path,
+                old_path: change.old_path,
+                status: change.status,
+                diff: None,
+            },
+            None => FileHistoryEntry {
+                commit,
+                path: current.clone(),
+                old_path: None,
+                status: FileStatus::Modified,
+                diff: None,
+            },
+        };
+        current = entry.old_path.clone().unwrap_or_else(|| entry.path.clone());
+        entries.push(entry);
+    }
+    entries.drain(..skip.min(entries.len()));
+
+    if include_diff {
+        for entry in &mut entries {
+            entry.diff = Some(
+                get_file_diff_in_commit(repo_path, &entry.commit.hash, &entry.path, entry.old_path.as_deref()).await?,
+            );
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Diff of one file in one commit, against the first parent
+///
+/// Passing the old path shows a rename as a rename instead of an add.
+pub async fn get_file_diff_in_commit(
+    repo_path: &Path,
+    hash: &str,
+    file_path: &str,
+    old_path: Option<&str>,
+) -> GitResult<String> {
+    let mut args = vec!["show", "--format=", "--no-color", "-M", "--diff-merges=first-parent", hash, "--", file_path];
+    if let Some(old_path) = old_path {
+        args.push(old_path);
+    }
+    execute_string(repo_path, &args).await
+}
+
+/// Read a file as it was at a revision (`git show <rev>:<path>`)
+pub async fn get_file_at_revision(repo_path: &Path, revision: &str, file_path: &str) -> GitResult<FileAtRevision> {
+    // Paths after the colon are relative to the repository root
+    let object = format!("{}:{}", revision, file_path.trim_start_matches("./"));
+
+    let size: u64 = execute_string(repo_path, &["cat-file", "-s", &object])
+        .await?
+        .trim()
+        .parse()
+        .unwrap_or(0);
+
+    let mut result = FileAtRevision {
+        revision: revision.to_string(),
+        path: file_path.to_string(),
+        size,
+        is_binary: false,
+        content: None,
+    };
+    if size > MAX_FILE_SIZE {
+        return Ok(result);
+    }
+
+    let output = execute(repo_path, &["show", &object]).await?;
+    if !output.status.success() {
+        return Err(GitError {
+            message: String::from_utf8_lossy(&output.stderr).to_string(),
+            command: format!("show {}", object),
+            exit_code: output.status.code(),
+        });
+    }
+
+    // Same heuristic as git: a NUL byte near the start means binary
+    let probe = &output.stdout[..output.stdout.len().min(8000)];
+    result.is_binary = probe.contains(&0);
+    if !result.is_binary {
+        result.content = Some(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+
+    Ok(result)
+}
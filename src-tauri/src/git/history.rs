@@ -1,79 +1,230 @@
+use std::collections::HashMap;
 use std::path::Path;
-use super::executor::{execute_string, GitResult};
-use super::types::CommitInfo;
-
-/// Get the last 50 commits from the repository
-pub async fn get_history(repo_path: &Path) -> GitResult<Vec<CommitInfo>> {
-    // Format: hash | abbreviation | author | email | timestamp (iso) | message
-    let format = "%H\t%h\t%an\t%ae\t%ad\t%s";
-    let output = execute_string(
-        repo_path, 
-        &[
-            "log", 
-            "-n", "50", 
-            "--date=iso-strict", 
-            &format!("--format={}", format)
-        ]
-    ).await?;
-
-    let mut commits = Vec::new();
-
-    for line in output.lines() {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() < 6 {
-            continue;
-        }
+use super::executor::{execute_string, GitError, GitResult};
+use super::signing::SignatureStatus;
+use super::types::{CommitInfo, FileChange, FileStatus};
+
+/// Format: hash | abbreviation | author | email | timestamp (iso) | signature | signer | message
+///
+/// The signature fields are left empty: `%G?` would run gpg for every signed commit,
+/// so listings verify signatures separately for the rows shown.
+pub(crate) const LOG_FORMAT: &str = "%H\t%h\t%an\t%ae\t%ad\t\t\t%s";
 
-        commits.push(CommitInfo {
-            hash: parts[0].to_string(),
-            short_hash: parts[1].to_string(),
-            author: parts[2].to_string(),
-            email: parts[3].to_string(),
-            timestamp: parts[4].to_string(),
-            message: parts[5].to_string(),
-        });
+/// Parse one line of `git log --format=LOG_FORMAT --date=iso-strict`
+pub(crate) fn parse_log_line(line: &str) -> Option<CommitInfo> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() < 8 {
+        return None;
     }
 
-    Ok(commits)
+    Some(CommitInfo {
+        hash: parts[0].to_string(),
+        short_hash: parts[1].to_string(),
+        author: parts[2].to_string(),
+        email: parts[3].to_string(),
+        timestamp: parts[4].to_string(),
+        signature: SignatureStatus::from_code(parts[5]),
+        signer: Some(parts[6].to_string()).filter(|s| !s.is_empty()),
+        message: parts[7..].join("\t"),
+    })
 }
 
-/// Get files changed in a specific commit
-pub async fn get_commit_files(repo_path: &Path, commit_hash: &str) -> GitResult<Vec<super::types::FileChange>> {
-    let output = execute_string(
-        repo_path,
-        &["show", "--name-status", "--oneline", commit_hash]
-    ).await?;
+/// Map a `--name-status` code such as `M` or `R087` to a status and similarity
+fn parse_status_code(code: &str) -> (FileStatus, Option<u8>) {
+    let status = match code.chars().next().unwrap_or('M') {
+        'A' => FileStatus::Added,
+        'D' => FileStatus::Deleted,
+        'R' => FileStatus::Renamed,
+        'C' => FileStatus::Copied,
+        _ => FileStatus::Modified,
+    };
+    (status, code.get(1..).and_then(|s| s.parse().ok()))
+}
 
+/// Parse `-z --name-status` output, where every code and path is NUL-terminated
+pub(crate) fn parse_name_status_z(output: &str) -> Vec<FileChange> {
     let mut changes = Vec::new();
-    // First line is commit info, skip it
-    for line in output.lines().skip(1) {
-        if line.is_empty() { continue; }
-        
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 2 { continue; }
-
-        let status_code = parts[0].chars().next().unwrap_or('M');
-        let path = parts[1].to_string();
-
-        let status = match status_code {
-            'A' => super::types::FileStatus::Added,
-            'D' => super::types::FileStatus::Deleted,
-            'R' => super::types::FileStatus::Renamed,
-            'C' => super::types::FileStatus::Copied,
-            _ => super::types::FileStatus::Modified,
+    let mut fields = output.split('\0');
+
+    while let Some(code) = fields.next().filter(|c| !c.is_empty()) {
+        let (status, similarity) = parse_status_code(code);
+        let Some(first) = fields.next() else { break };
+
+        let change = if matches!(status, FileStatus::Renamed | FileStatus::Copied) {
+            let Some(path) = fields.next() else { break };
+            let mut change = FileChange::new(path.to_string(), status);
+            change.old_path = Some(first.to_string());
+            change.similarity = similarity;
+            change
+        } else {
+            FileChange::new(first.to_string(), status)
         };
+        changes.push(change);
+    }
+
+    changes
+}
+
+/// Parse `-z --numstat` output into added/deleted counts keyed by new path
+///
+/// Binary files report `-` for both counts. Renames leave the path empty and follow
+/// with the old and new path as separate fields.
+fn parse_numstat_z(output: &str) -> HashMap<String, (Option<u32>, Option<u32>)> {
+    let mut stats = HashMap::new();
+    let mut fields = output.split('\0');
 
-        changes.push(super::types::FileChange { path, status });
+    while let Some(entry) = fields.next().filter(|e| !e.is_empty()) {
+        let mut parts = entry.splitn(3, '\t');
+        let additions = parts.next().and_then(|n| n.parse().ok());
+        let deletions = parts.next().and_then(|n| n.parse().ok());
+        let mut path = parts.next().unwrap_or_default();
+        if path.is_empty() {
+            fields.next(); // Old path
+            path = fields.next().unwrap_or_default();
+        }
+        stats.insert(path.to_string(), (additions, deletions));
+    }
+
+    stats
+}
+
+/// Resolve the commit to diff `commit_hash` against: its `parent`-th parent (1-based,
+/// the first by default), or `None` for a root commit
+async fn diff_base(repo_path: &Path, commit_hash: &str, parent: Option<usize>) -> GitResult<Option<String>> {
+    let output = execute_string(repo_path, &["rev-list", "--parents", "-n", "1", commit_hash, "--"]).await?;
+    let parents: Vec<&str> = output.split_whitespace().skip(1).collect();
+    if parents.is_empty() {
+        return Ok(None);
     }
 
+    let index = parent.unwrap_or(1);
+    match index.checked_sub(1).and_then(|i| parents.get(i)) {
+        Some(base) => Ok(Some(base.to_string())),
+        None => Err(GitError {
+            message: format!("Commit has {} parent(s), cannot diff against parent {}", parents.len(), index),
+            command: format!("rev-list --parents -n 1 {}", commit_hash),
+            exit_code: None,
+        }),
+    }
+}
+
+/// Get files changed in a specific commit
+///
+/// Merge commits are diffed against `parent` (1-based, the first by default) and root
+/// commits against the empty tree. Renames and copies carry their old path and
+/// similarity; line counts come from `--numstat`.
+pub async fn get_commit_files(repo_path: &Path, commit_hash: &str, parent: Option<usize>) -> GitResult<Vec<FileChange>> {
+    let base = diff_base(repo_path, commit_hash, parent).await?;
+    let range: Vec<&str> = match base.as_deref() {
+        Some(base) => vec![base, commit_hash],
+        None => vec!["--root", commit_hash],
+    };
+
+    let diff_tree = |format: &'static str| {
+        let mut args = vec!["diff-tree", "-z", "-r", "--no-commit-id", format, "-M", "-C"];
+        args.extend(&range);
+        args
+    };
+
+    let names = execute_string(repo_path, &diff_tree("--name-status")).await?;
+    let stats = execute_string(repo_path, &diff_tree("--numstat")).await?;
+    let stats = parse_numstat_z(&stats);
+
+    let mut changes = parse_name_status_z(&names);
+    for change in &mut changes {
+        if let Some(&(additions, deletions)) = stats.get(&change.path) {
+            change.additions = additions;
+            change.deletions = deletions;
+        }
+    }
     Ok(changes)
 }
 
-/// Get the diff of a specific file in a specific commit
-pub async fn get_commit_file_diff(repo_path: &Path, commit_hash: &str, file_path: &str) -> GitResult<String> {
-    // We compare the commit with its parent to see what changed in that file
-    execute_string(
-        repo_path,
-        &["diff", "--no-color", &format!("{}~1", commit_hash), commit_hash, "--", file_path]
-    ).await
+/// Get the diff of a specific file in a specific commit, against the same parent as
+/// [`get_commit_files`]
+///
+/// Passing the old path of a rename or copy shows it as such instead of an add.
+pub async fn get_commit_file_diff(
+    repo_path: &Path,
+    commit_hash: &str,
+    file_path: &str,
+    old_path: Option<&str>,
+    parent: Option<usize>,
+) -> GitResult<String> {
+    match diff_base(repo_path, commit_hash, parent).await? {
+        Some(base) => {
+            let mut args = vec!["diff", "--no-color", "-M", "-C", &base, commit_hash, "--", file_path];
+            if let Some(old_path) = old_path {
+                args.push(old_path);
+            }
+            execute_string(repo_path, &args).await
+        }
+        // A root commit has no parent; show its whole content as added
+        None => execute_string(repo_path, &["show", "--no-color", "--format=", commit_hash, "--", file_path]).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_status_z() {
+        let output = concat!(
+            "M\0src/main.rs\0",
+            "A\0docs/new file.md\0",
+            "D\0old\tname.txt\0",
+            "R087\0a b.txt\0t\tab.txt\0",
+            "C100\0other\0copy of other\0",
+        );
+        let changes = parse_name_status_z(output);
+        assert_eq!(changes.len(), 5);
+
+        assert!(matches!(changes[0].status, FileStatus::Modified));
+        assert_eq!(changes[0].path, "src/main.rs");
+        assert!(changes[0].old_path.is_none());
+
+        assert!(matches!(changes[1].status, FileStatus::Added));
+        assert_eq!(changes[1].path, "docs/new file.md");
+
+        assert!(matches!(changes[2].status, FileStatus::Deleted));
+        assert_eq!(changes[2].path, "old\tname.txt");
+
+        assert!(matches!(changes[3].status, FileStatus::Renamed));
+        assert_eq!(changes[3].path, "t\tab.txt");
+        assert_eq!(changes[3].old_path.as_deref(), Some("a b.txt"));
+        assert_eq!(changes[3].similarity, Some(87));
+
+        assert!(matches!(changes[4].status, FileStatus::Copied));
+        assert_eq!(changes[4].path, "copy of other");
+        assert_eq!(changes[4].old_path.as_deref(), Some("other"));
+        assert_eq!(changes[4].similarity, Some(100));
+    }
+
+    #[test]
+    fn test_parse_name_status_z_truncated() {
+        assert!(parse_name_status_z("").is_empty());
+        // A rename missing its new path is dropped rather than misread
+        let changes = parse_name_status_z("M\0a.txt\0R100\0b.txt");
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "a.txt");
+    }
+
+    #[test]
+    fn test_parse_numstat_z() {
+        let output = concat!(
+            "3\t1\tsrc/main.rs\0",
+            "-\t-\tassets/logo one.png\0",
+            "5\t2\t\0a b.txt\0t\tab.txt\0",
+            "1\t0\t\0other\0copy of other\0",
+        );
+        let stats = parse_numstat_z(output);
+        assert_eq!(stats.len(), 4);
+
+        assert_eq!(stats["src/main.rs"], (Some(3), Some(1)));
+        assert_eq!(stats["assets/logo one.png"], (None, None));
+        assert_eq!(stats["t\tab.txt"], (Some(5), Some(2)));
+        assert_eq!(stats["copy of other"], (Some(1), Some(0)));
+        assert!(!stats.contains_key("a b.txt"));
+    }
 }
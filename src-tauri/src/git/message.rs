@@ -0,0 +1,406 @@
+//! Commit message module
+//!
+//! Structured commit messages, templates, and message linting.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute_string, GitResult};
+
+/// A commit message split into subject, body and trailers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitMessage {
+    pub subject: String,
+    pub body: String,
+    pub trailers: Vec<Trailer>,
+}
+
+/// A `Key: value` trailer such as `Co-authored-by` or `Refs`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl CommitMessage {
+    /// Build the full message passed to `git commit`
+    pub fn format(&self) -> String {
+        let mut message = self.subject.trim().to_string();
+
+        let body = self.body.trim();
+        if !body.is_empty() {
+            message.push_str("\n\n");
+            message.push_str(body);
+        }
+
+        if !self.trailers.is_empty() {
+            message.push_str("\n\n");
+            let lines: Vec<String> = self
+                .trailers
+                .iter()
+                .map(|t| format!("{}: {}", t.key.trim(), t.value.trim()))
+                .collect();
+            message.push_str(&lines.join("\n"));
+        }
+
+        message
+    }
+
+    /// Whether the message carries no text at all
+    pub fn is_empty(&self) -> bool {
+        self.subject.trim().is_empty() && self.body.trim().is_empty() && self.trailers.is_empty()
+    }
+
+    /// Add a trailer unless an identical one is already present
+    pub fn add_trailer(&mut self, trailer: Trailer) {
+        let exists = self.trailers.iter().any(|t| {
+            t.key.eq_ignore_ascii_case(&trailer.key) && t.value.trim() == trailer.value.trim()
+        });
+        if !exists {
+            self.trailers.push(trailer);
+        }
+    }
+
+    /// Split a raw message into subject, body and trailing trailer block
+    pub fn parse(raw: &str) -> Self {
+        let cleaned = strip_comments(raw);
+        let mut lines = cleaned.lines();
+        let subject = lines.next().unwrap_or_default().trim().to_string();
+        let rest: Vec<&str> = lines.collect();
+
+        // The trailer block is the last paragraph, if every line in it looks like a trailer
+        let rest_text = rest.join("\n");
+        let rest_text = rest_text.trim();
+        let (body, trailers) = match rest_text.rfind("\n\n") {
+            Some(pos) => {
+                let last = &rest_text[pos + 2..];
+                match parse_trailer_block(last) {
+                    Some(trailers) => (rest_text[..pos].trim().to_string(), trailers),
+                    None => (rest_text.to_string(), Vec::new()),
+                }
+            }
+            None => match parse_trailer_block(rest_text) {
+                Some(trailers) if !rest_text.is_empty() => (String::new(), trailers),
+                _ => (rest_text.to_string(), Vec::new()),
+            },
+        };
+
+        Self {
+            subject,
+            body,
+            trailers,
+        }
+    }
+}
+
+/// Parse a paragraph made up only of `Key: value` lines
+fn parse_trailer_block(block: &str) -> Option<Vec<Trailer>> {
+    let mut trailers = Vec::new();
+    for line in block.lines() {
+        let (key, value) = line.split_once(": ")?;
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+        trailers.push(Trailer::new(key, value));
+    }
+    Some(trailers)
+}
+
+/// Remove `#` comment lines, as git does for templates
+fn strip_comments(raw: &str) -> String {
+    raw.lines()
+        .filter(|l| !l.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Load the commit message template for a repository
+///
+/// Uses `commit.template` when configured, then falls back to a `.gitmessage`
+/// file in the repository root and in the home directory.
+pub async fn load_commit_template(repo_path: &Path) -> GitResult<Option<CommitMessage>> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+
+    if let Ok(configured) = execute_string(repo_path, &["config", "--path", "commit.template"]).await {
+        let configured = configured.trim();
+        if !configured.is_empty() {
+            let template = PathBuf::from(configured);
+            if template.is_absolute() {
+                candidates.push(template);
+            } else {
+                candidates.push(repo_path.join(template));
+            }
+        }
+    }
+
+    candidates.push(repo_path.join(".gitmessage"));
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".gitmessage"));
+    }
+
+    for candidate in candidates {
+        if let Ok(content) = tokio::fs::read_to_string(&candidate).await {
+            let message = CommitMessage::parse(&content);
+            if !message.is_empty() {
+                return Ok(Some(message));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Configurable rules for commit message linting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintRules {
+    /// Maximum subject length, 0 disables the check
+    pub max_subject_length: usize,
+    /// Maximum body line length, 0 disables the check
+    pub max_body_line_length: usize,
+    /// Require the second line of the message to be blank
+    pub blank_second_line: bool,
+    /// Require a Conventional Commits `type(scope): subject` header
+    pub conventional_commits: bool,
+    /// Allowed Conventional Commits types
+    pub allowed_types: Vec<String>,
+}
+
+impl Default for LintRules {
+    fn default() -> Self {
+        Self {
+            max_subject_length: 72,
+            max_body_line_length: 0,
+            blank_second_line: true,
+            conventional_commits: false,
+            allowed_types: [
+                "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+}
+
+/// A single lint rule violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintIssue {
+    pub rule: String,
+    pub message: String,
+    pub line: usize,
+}
+
+/// Check a full commit message against the given rules
+pub fn lint_message(message: &str, rules: &LintRules) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let lines: Vec<&str> = message.lines().collect();
+    let subject = lines.first().copied().unwrap_or_default();
+
+    if subject.trim().is_empty() {
+        issues.push(LintIssue {
+            rule: "subject-empty".to_string(),
+            message: "Subject must not be empty".to_string(),
+            line: 1,
+        });
+        return issues;
+    }
+
+    let subject_len = subject.chars().count();
+    if rules.max_subject_length > 0 && subject_len > rules.max_subject_length {
+        issues.push(LintIssue {
+            rule: "subject-length".to_string(),
+            message: format!(
+                "Subject is {} characters long, the limit is {}",
+                subject_len, rules.max_subject_length
+            ),
+            line: 1,
+        });
+    }
+
+    if rules.blank_second_line {
+        if let Some(second) = lines.get(1) {
+            if !second.trim().is_empty() {
+                issues.push(LintIssue {
+                    rule: "blank-second-line".to_string(),
+                    message: "The second line must be blank".to_string(),
+                    line: 2,
+                });
+            }
+        }
+    }
+
+    if rules.max_body_line_length > 0 {
+        for (i, line) in lines.iter().enumerate().skip(2) {
+            let len = line.chars().count();
+            // Long URLs cannot be wrapped
+            if len > rules.max_body_line_length && !line.contains("://") {
+                issues.push(LintIssue {
+                    rule: "body-line-length".to_string(),
+                    message: format!(
+                        "Line is {} characters long, the limit is {}",
+                        len, rules.max_body_line_length
+                    ),
+                    line: i + 1,
+                });
+            }
+        }
+    }
+
+    if rules.conventional_commits {
+        if let Some(issue) = lint_conventional_header(subject, &rules.allowed_types) {
+            issues.push(issue);
+        }
+    }
+
+    issues
+}
+
+/// Validate a `type(scope)!: description` header
+fn lint_conventional_header(subject: &str, allowed_types: &[String]) -> Option<LintIssue> {
+    let issue = |message: String| LintIssue {
+        rule: "conventional-commits".to_string(),
+        message,
+        line: 1,
+    };
+
+    // Autosquash and revert subjects are generated by git
+    if subject.starts_with("fixup! ")
+        || subject.starts_with("squash! ")
+        || subject.starts_with("amend! ")
+        || subject.starts_with("Revert \"")
+        || subject.starts_with("Merge ")
+    {
+        return None;
+    }
+
+    let Some((header, description)) = subject.split_once(": ") else {
+        return Some(issue("Subject must follow `type(scope): description`".to_string()));
+    };
+
+    let header = header.strip_suffix('!').unwrap_or(header);
+    let commit_type = match header.split_once('(') {
+        Some((commit_type, scope)) => {
+            if !scope.ends_with(')') || scope.len() < 2 {
+                return Some(issue("Scope must be wrapped in parentheses".to_string()));
+            }
+            commit_type
+        }
+        None => header,
+    };
+
+    if !allowed_types.is_empty() && !allowed_types.iter().any(|t| t == commit_type) {
+        return Some(issue(format!(
+            "Type `{}` is not one of: {}",
+            commit_type,
+            allowed_types.join(", ")
+        )));
+    }
+
+    if description.trim().is_empty() {
+        return Some(issue("Description must not be empty".to_string()));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_rule(issues: &[LintIssue], rule: &str) -> bool {
+        issues.iter().any(|i| i.rule == rule)
+    }
+
+    #[test]
+    fn test_parse_subject_body_and_trailers() {
+        let message = CommitMessage::parse(
+            "Fix parser\n\nHandle empty input.\n\nSecond paragraph.\n\nSigned-off-by: A <a@example.com>\nCo-authored-by: B <b@example.com>\n",
+        );
+        assert_eq!(message.subject, "Fix parser");
+        assert_eq!(message.body, "Handle empty input.\n\nSecond paragraph.");
+        assert_eq!(message.trailers.len(), 2);
+        assert_eq!(message.trailers[0].key, "Signed-off-by");
+        assert_eq!(message.trailers[1].value, "B <b@example.com>");
+    }
+
+    #[test]
+    fn test_parse_last_paragraph_without_trailers_is_body() {
+        let message = CommitMessage::parse("Subject\n\nSee: the docs for why\nthis matters");
+        assert_eq!(message.body, "See: the docs for why\nthis matters");
+        assert!(message.trailers.is_empty());
+    }
+
+    #[test]
+    fn test_parse_trailers_only_and_comments() {
+        let message = CommitMessage::parse("# template\nSubject\n# hint\n\nRefs: #12");
+        assert_eq!(message.subject, "Subject");
+        assert!(message.body.is_empty());
+        assert_eq!(message.trailers.len(), 1);
+        assert_eq!(message.trailers[0].value, "#12");
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let raw = "Subject\n\nBody line\n\nSigned-off-by: A <a@example.com>";
+        assert_eq!(CommitMessage::parse(raw).format(), raw);
+    }
+
+    #[test]
+    fn test_add_trailer_skips_duplicates() {
+        let mut message = CommitMessage::parse("Subject");
+        message.add_trailer(Trailer::new("Signed-off-by", "A <a@example.com>"));
+        message.add_trailer(Trailer::new("signed-off-by", "A <a@example.com>"));
+        assert_eq!(message.trailers.len(), 1);
+    }
+
+    #[test]
+    fn test_lint_empty_subject() {
+        let issues = lint_message("\n\nbody", &LintRules::default());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, "subject-empty");
+    }
+
+    #[test]
+    fn test_lint_lengths_and_blank_line() {
+        let lint_rules = LintRules {
+            max_subject_length: 10,
+            max_body_line_length: 20,
+            ..LintRules::default()
+        };
+        let issues = lint_message(
+            "A subject that is too long\nnot blank\nshort\nthis body line is much too long\nhttps://example.com/a/very/long/url/that/cannot/wrap",
+            &lint_rules,
+        );
+        assert!(has_rule(&issues, "subject-length"));
+        assert!(has_rule(&issues, "blank-second-line"));
+        let long_lines: Vec<usize> = issues.iter().filter(|i| i.rule == "body-line-length").map(|i| i.line).collect();
+        assert_eq!(long_lines, vec![4]);
+    }
+
+    #[test]
+    fn test_lint_conventional_commits() {
+        let lint_rules = LintRules {
+            conventional_commits: true,
+            ..LintRules::default()
+        };
+        let conventional = |subject: &str| has_rule(&lint_message(subject, &lint_rules), "conventional-commits");
+        assert!(!conventional("feat(ui): add button"));
+        assert!(!conventional("fix!: drop legacy flag"));
+        assert!(!conventional("fixup! feat(ui): add button"));
+        assert!(conventional("add button"));
+        assert!(conventional("feature: add button"));
+        assert!(conventional("feat(ui: add button"));
+    }
+}
@@ -2,16 +2,43 @@
 //!
 //! Handles all Git CLI interactions including status, branches, and commits.
 
+pub mod authors;
+pub mod blame;
 pub mod branches;
+pub mod checkout;
+pub mod cleanup;
+pub mod compare;
+pub mod config;
 pub mod executor;
+pub mod file_history;
 pub mod status;
 pub mod types;
 pub mod operations;
 pub mod history;
+pub mod message;
+pub mod naming;
+pub mod search;
+pub mod signing;
 
-pub use branches::list_branches;
-pub use executor::is_git_repo;
-pub use status::get_status;
-pub use history::{get_history, get_commit_files, get_commit_file_diff};
+pub use authors::{get_author_index, search_authors, AuthorEntry, AuthorMatch};
+pub use blame::{blame_file, blame_file_batched, cancel_blame, BlameOptions, BlameRange};
+pub use branches::{
+    create_branch_at, delete_remote_branch, get_default_branch, list_branches, rename_branch, set_branch_upstream,
+};
+pub use checkout::{checkout_with, CheckoutMode, CheckoutResult};
+pub use cleanup::{
+    delete_branches, find_stale_branches, list_deleted_branches, restore_branch, DeletedBranch, DeletedBranchRecord,
+    StaleBranch,
+};
+pub use compare::{compare_refs, get_compare_file_diff, preview_merge, CompareResult, MergePreview};
+pub use config::{ConfigEntry, ConfigScope, EffectiveIdentity, IncludeIf};
+pub use executor::{detect_repository_kind, ensure_writable, is_git_repo};
+pub use status::{get_status, get_tracking_info};
+pub use file_history::{get_file_at_revision, get_file_diff_in_commit, get_file_history, FileAtRevision, FileHistoryEntry};
+pub use history::{get_commit_files, get_commit_file_diff};
+pub use message::{CommitMessage, LintRules, LintIssue, lint_message, load_commit_template};
+pub use naming::{suggest_branch_name, validate_branch_name, BranchNameCheck, BranchNameInput};
+pub use search::{cancel_commit_search, search_commits, search_commits_batched, CommitQuery};
+pub use signing::{get_signing_config, verify_commit_signatures, CommitSignature, SigningConfig};
 pub use types::*;
 pub use operations::*;
@@ -0,0 +1,324 @@
+//! Branch naming
+//!
+//! Validation of branch names and generation from templates like
+//! `feature/{ticket}-{slug}`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute, execute_string, GitError, GitResult};
+
+/// Longest slug generated from a description
+const MAX_SLUG_LENGTH: usize = 48;
+
+/// Result of validating a proposed branch name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchNameCheck {
+    pub name: String,
+    pub valid: bool,
+    /// Why git rejects the name
+    pub error: Option<String>,
+    pub exists_locally: bool,
+    /// Remote-tracking branches with the same name, e.g. `origin/feature`
+    pub exists_on_remotes: Vec<String>,
+}
+
+/// Values substituted into a branch name template
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BranchNameInput {
+    pub ticket: Option<String>,
+    pub description: Option<String>,
+    /// Extra placeholders, e.g. `{"type": "fix"}`
+    pub values: HashMap<String, String>,
+}
+
+/// ASCII base letter of common accented Latin letters
+fn fold_accent(c: char) -> char {
+    match c.to_lowercase().next().unwrap_or(c) {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        other => other,
+    }
+}
+
+/// Turn free text into a lowercase, dash-separated branch name segment
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().map(fold_accent) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let mut slug = slug.trim_matches('-').to_string();
+    if slug.len() > MAX_SLUG_LENGTH {
+        // Cut at a word boundary when there is one
+        slug.truncate(MAX_SLUG_LENGTH);
+        if let Some(pos) = slug.rfind('-') {
+            slug.truncate(pos);
+        }
+    }
+    slug
+}
+
+/// Keep a ticket ID recognizable (`ABC-123`) while removing characters git rejects
+fn sanitize_ticket(ticket: &str) -> String {
+    let mut out = String::new();
+    for c in ticket.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            out.push(c);
+        } else if !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_matches('-').to_string()
+}
+
+/// Clean up what substitution can leave behind: empty segments, stray separators,
+/// and components git forbids
+fn tidy_branch_name(name: &str) -> String {
+    let segments: Vec<String> = name
+        .split('/')
+        .map(|segment| {
+            let mut segment = segment.trim_matches(|c| c == '-' || c == '.' || c == '_').to_string();
+            while segment.contains("--") {
+                segment = segment.replace("--", "-");
+            }
+            while segment.contains("..") {
+                segment = segment.replace("..", ".");
+            }
+            segment.strip_suffix(".lock").map(str::to_string).unwrap_or(segment)
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    segments.join("/")
+}
+
+/// Fill a template such as `feature/{ticket}-{slug}`
+///
+/// Known placeholders are `{ticket}`, `{slug}` and `{description}` (an alias of
+/// `{slug}`); any other `{key}` is looked up in `input.values`. Unknown or empty
+/// placeholders are dropped along with the separators around them.
+pub fn render_branch_template(template: &str, input: &BranchNameInput) -> String {
+    let slug = input.description.as_deref().map(slugify).unwrap_or_default();
+    let ticket = input.ticket.as_deref().map(sanitize_ticket).unwrap_or_default();
+
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            out.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let key = &rest[start + 1..start + len];
+        let value = match key {
+            "ticket" => ticket.clone(),
+            "slug" | "description" => slug.clone(),
+            other => input.values.get(other).map(|v| slugify(v)).unwrap_or_default(),
+        };
+        out.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+
+    tidy_branch_name(&out)
+}
+
+/// Check a branch name with `git check-ref-format --branch` and against existing branches
+pub async fn validate_branch_name(repo_path: &Path, name: &str) -> GitResult<BranchNameCheck> {
+    let output = execute(repo_path, &["check-ref-format", "--branch", name]).await?;
+    let normalized = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    let error = if name.trim().is_empty() {
+        Some("Branch name cannot be empty".to_string())
+    } else if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Some(stderr.trim().trim_start_matches("fatal: ").to_string())
+    } else if normalized != name {
+        // `@{-1}` and friends are expanded by --branch; they are not names
+        Some(format!("'{}' is not a valid branch name", name))
+    } else {
+        None
+    };
+
+    let local = format!("refs/heads/{}", name);
+    let exists_locally = execute(repo_path, &["show-ref", "--verify", "--quiet", &local])
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    let pattern = format!("refs/remotes/*/{}", name);
+    let exists_on_remotes = execute_string(repo_path, &["for-each-ref", "--format=%(refname:short)", &pattern])
+        .await
+        .map(|out| out.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default();
+
+    Ok(BranchNameCheck {
+        name: name.to_string(),
+        valid: error.is_none(),
+        error,
+        exists_locally,
+        exists_on_remotes,
+    })
+}
+
+/// Fail unless `name` is a valid branch name that does not exist yet
+pub async fn ensure_new_branch_name(repo_path: &Path, name: &str) -> GitResult<()> {
+    let check = validate_branch_name(repo_path, name).await?;
+    let message = match (check.error, check.exists_locally) {
+        (Some(error), _) => error,
+        (None, true) => format!("A branch named '{}' already exists", name),
+        (None, false) => return Ok(()),
+    };
+
+    Err(GitError {
+        message,
+        command: format!("branch {}", name),
+        exit_code: None,
+    })
+}
+
+/// Generate a valid, unused branch name from a template
+///
+/// A numeric suffix is added when the rendered name is already taken.
+pub async fn suggest_branch_name(repo_path: &Path, template: &str, input: &BranchNameInput) -> GitResult<String> {
+    let base = render_branch_template(template, input);
+    if base.is_empty() {
+        return Err(GitError {
+            message: "Template produced an empty branch name; provide a ticket or description".to_string(),
+            command: "check-ref-format".to_string(),
+            exit_code: None,
+        });
+    }
+
+    let mut candidate = base.clone();
+    for n in 2..100 {
+        let check = validate_branch_name(repo_path, &candidate).await?;
+        if let Some(error) = check.error {
+            return Err(GitError {
+                message: error,
+                command: "check-ref-format --branch".to_string(),
+                exit_code: None,
+            });
+        }
+        if !check.exists_locally && check.exists_on_remotes.is_empty() {
+            return Ok(candidate);
+        }
+        candidate = format!("{}-{}", base, n);
+    }
+
+    Err(GitError {
+        message: format!("Could not find an unused branch name for '{}'", base),
+        command: "check-ref-format --branch".to_string(),
+        exit_code: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(ticket: Option<&str>, description: Option<&str>) -> BranchNameInput {
+        BranchNameInput {
+            ticket: ticket.map(str::to_string),
+            description: description.map(str::to_string),
+            values: HashMap::new(),
+        }
+    }
+
+    /// A fresh repository with one commit on `main`
+    fn init_repo(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("pinax-naming-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for args in [
+            vec!["init", "-q", "-b", "main"],
+            vec!["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "-q", "--allow-empty", "-m", "init"],
+        ] {
+            let status = std::process::Command::new("git").args(&args).current_dir(&dir).status().unwrap();
+            assert!(status.success());
+        }
+        dir
+    }
+
+    fn validate(repo: &Path, name: &str) -> BranchNameCheck {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(validate_branch_name(repo, name)).unwrap()
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Fix the Login page!"), "fix-the-login-page");
+        assert_eq!(slugify("  --Crème brûlée à la carte--  "), "creme-brulee-a-la-carte");
+        assert_eq!(slugify("path/to  file.rs"), "path-to-file-rs");
+        assert_eq!(slugify("日本語"), "");
+    }
+
+    #[test]
+    fn test_slugify_truncates_at_word_boundary() {
+        let slug = slugify("support exporting every repository in a workspace to a portable file");
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert_eq!(slug, "support-exporting-every-repository-in-a");
+
+        let slug = slugify(&"a".repeat(60));
+        assert_eq!(slug.len(), MAX_SLUG_LENGTH);
+    }
+
+    #[test]
+    fn test_render_branch_template() {
+        let full = input(Some("ABC-123"), Some("Add dark mode"));
+        assert_eq!(render_branch_template("feature/{ticket}-{slug}", &full), "feature/ABC-123-add-dark-mode");
+        assert_eq!(render_branch_template("{description}", &full), "add-dark-mode");
+
+        let mut typed = input(None, Some("Crash on start"));
+        typed.values.insert("type".to_string(), "Bug Fix".to_string());
+        assert_eq!(render_branch_template("{type}/{slug}", &typed), "bug-fix/crash-on-start");
+    }
+
+    #[test]
+    fn test_render_branch_template_drops_empty_placeholders() {
+        let no_ticket = input(None, Some("Add dark mode"));
+        assert_eq!(render_branch_template("feature/{ticket}-{slug}", &no_ticket), "feature/add-dark-mode");
+        assert_eq!(render_branch_template("{unknown}/{slug}", &no_ticket), "add-dark-mode");
+        assert_eq!(render_branch_template("feature/{ticket}", &input(None, None)), "feature");
+        assert_eq!(render_branch_template("{ticket}.lock", &input(Some("a b"), None)), "a-b");
+        assert_eq!(render_branch_template("open/{slug", &no_ticket), "open/{slug");
+    }
+
+    #[test]
+    fn test_validate_branch_name() {
+        let repo = init_repo("validate");
+
+        let check = validate(&repo, "feature/add-dark-mode");
+        assert!(check.valid);
+        assert!(check.error.is_none());
+        assert!(!check.exists_locally);
+
+        let check = validate(&repo, "main");
+        assert!(check.valid);
+        assert!(check.exists_locally);
+
+        for name in ["", "  ", "has space", "double..dot", "ends.lock", "-leading", "@{-1}"] {
+            let check = validate(&repo, name);
+            assert!(!check.valid, "{:?} should be rejected", name);
+            assert!(check.error.is_some());
+        }
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+}
@@ -1,5 +1,8 @@
 use std::path::Path;
-use super::executor::{execute, execute_string, execute_global_string, GitResult, GitError};
+use super::executor::{ensure_writable, execute, execute_string, execute_global_string, git_dir, GitResult, GitError};
+use super::message::{lint_message, CommitMessage, Trailer};
+use super::signing::{commit_signing_args, signing_error, tag_signing_args};
+use super::types::CommitOptions;
 
 /// Retrieve a value from the global Git configuration
 pub async fn get_global_config(key: &str) -> GitResult<String> {
@@ -31,7 +34,20 @@ pub async fn fetch(path: &Path) -> GitResult<()> {
 
 /// Pull changes from remote
 pub async fn pull(path: &Path) -> GitResult<()> {
-    let output = execute(path, &["pull"]).await?;
+    pull_with_args(path, &[]).await
+}
+
+/// Pull changes from remote, refusing anything but a fast-forward
+pub async fn pull_ff_only(path: &Path) -> GitResult<()> {
+    pull_with_args(path, &["--ff-only"]).await
+}
+
+async fn pull_with_args(path: &Path, extra: &[&str]) -> GitResult<()> {
+    ensure_writable(path, "pull").await?;
+    let mut args = vec!["pull"];
+    args.extend_from_slice(extra);
+
+    let output = execute(path, &args).await?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
 
@@ -39,14 +55,14 @@ pub async fn pull(path: &Path) -> GitResult<()> {
         if stderr.contains("You are not currently on a branch") || stderr.contains("not currently on a branch") {
             return Err(GitError {
                 message: "Pull failed: You are in 'detached HEAD' state. Please switch to a local branch or create a branch from this commit to sync changes.".to_string(),
-                command: "pull".to_string(),
+                command: args.join(" "),
                 exit_code: output.status.code(),
             });
         }
 
         return Err(GitError {
             message: stderr.to_string(),
-            command: "pull".to_string(),
+            command: args.join(" "),
             exit_code: output.status.code(),
         });
     }
@@ -112,6 +128,7 @@ pub async fn push_initial(path: &Path) -> GitResult<()> {
 
 /// Stage a file
 pub async fn stage_file(path: &Path, file_path: &str) -> GitResult<()> {
+    ensure_writable(path, "add").await?;
     let output = execute(path, &["add", file_path]).await?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -126,6 +143,7 @@ pub async fn stage_file(path: &Path, file_path: &str) -> GitResult<()> {
 
 /// Unstage a file
 pub async fn unstage_file(path: &Path, file_path: &str) -> GitResult<()> {
+    ensure_writable(path, "reset").await?;
     // If it's a new file (untracked), we use a different reset command or just remove it from index
     // But 'git reset HEAD <file>' works for both modified and added files
     let output = execute(path, &["reset", "HEAD", "--", file_path]).await?;
@@ -141,9 +159,93 @@ pub async fn unstage_file(path: &Path, file_path: &str) -> GitResult<()> {
 }
 
 /// Commit staged changes
-pub async fn commit(path: &Path, message: &str) -> GitResult<()> {
-    // Just commit what is currently staged
-    let output = execute(path, &["commit", "-m", message]).await?;
+pub async fn commit(path: &Path, message: &CommitMessage, options: &CommitOptions) -> GitResult<()> {
+    ensure_writable(path, "commit").await?;
+    if options.fixup.is_some() && options.squash.is_some() {
+        return Err(GitError {
+            message: "A commit cannot be both a fixup and a squash".to_string(),
+            command: "commit".to_string(),
+            exit_code: None,
+        });
+    }
+
+    // Trailers alone are not a message; amends and fixups then keep the existing one
+    let has_text = !message.subject.trim().is_empty() || !message.body.trim().is_empty();
+    let mut message = message.clone();
+    for co_author in &options.co_authors {
+        message.add_trailer(Trailer::new("Co-authored-by", co_author));
+    }
+    let trailers = message.trailers.clone();
+    let message = message.format();
+
+    if let Some(rules) = &options.lint {
+        if has_text {
+            let issues = lint_message(&message, rules);
+            if !issues.is_empty() {
+                let details: Vec<String> = issues
+                    .iter()
+                    .map(|i| format!("line {}: {}", i.line, i.message))
+                    .collect();
+                return Err(GitError {
+                    message: format!("Commit message failed lint checks:\n{}", details.join("\n")),
+                    command: "commit".to_string(),
+                    exit_code: None,
+                });
+            }
+        }
+    }
+
+    let mut args: Vec<String> = vec!["commit".to_string()];
+
+    if options.amend {
+        args.push("--amend".to_string());
+    }
+    if let Some(hash) = &options.fixup {
+        args.push(format!("--fixup={}", hash));
+    }
+    if let Some(hash) = &options.squash {
+        args.push(format!("--squash={}", hash));
+    }
+    if options.allow_empty {
+        args.push("--allow-empty".to_string());
+    }
+    if options.no_verify {
+        args.push("--no-verify".to_string());
+    }
+    if options.signoff {
+        args.push("--signoff".to_string());
+    }
+    args.extend(commit_signing_args(path, options.sign).await);
+    if let Some(author) = &options.author {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(date) = &options.date {
+        args.push(format!("--date={}", date));
+    }
+
+    // Amend and fixup commits may reuse the existing message
+    if !has_text {
+        if options.amend || options.squash.is_some() {
+            // squash! commits open the editor by default
+            args.push("--no-edit".to_string());
+        } else if options.fixup.is_none() {
+            return Err(GitError {
+                message: "Commit message cannot be empty".to_string(),
+                command: "commit".to_string(),
+                exit_code: None,
+            });
+        }
+        // Appended to the reused message instead of replacing it
+        for trailer in &trailers {
+            args.push(format!("--trailer={}: {}", trailer.key.trim(), trailer.value.trim()));
+        }
+    } else {
+        args.push("-m".to_string());
+        args.push(message);
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let output = execute(path, &arg_refs).await?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         // Special case: nothing to commit (including localized messages)
@@ -154,6 +256,10 @@ pub async fn commit(path: &Path, message: &str) -> GitResult<()> {
             return Ok(());
         }
 
+        if let Some(err) = signing_error(&stderr, "commit", output.status.code()) {
+            return Err(err);
+        }
+
         return Err(GitError {
             message: stderr.to_string(),
             command: "commit".to_string(),
@@ -163,76 +269,172 @@ pub async fn commit(path: &Path, message: &str) -> GitResult<()> {
     Ok(())
 }
 
-/// Undo the last commit (soft reset)
-pub async fn undo_commit(path: &Path) -> GitResult<()> {
-    let output = execute(path, &["reset", "--soft", "HEAD~1"]).await?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+/// Check whether HEAD is already contained in a remote-tracking branch
+///
+/// Used to warn before amending or rewording commits that others may have pulled.
+pub async fn is_head_pushed(path: &Path) -> GitResult<bool> {
+    let output = execute_string(path, &["branch", "-r", "--contains", "HEAD"]).await?;
+    Ok(!output.trim().is_empty())
+}
+
+/// Change the message of any commit in the current branch
+///
+/// HEAD is amended directly. Older commits get an `amend!` commit which is then
+/// folded into its target by a non-interactive autosquash rebase.
+pub async fn reword_commit(path: &Path, hash: &str, message: &str) -> GitResult<()> {
+    ensure_writable(path, "reword").await?;
+    if message.trim().is_empty() {
         return Err(GitError {
-            message: stderr.to_string(),
-            command: "reset --soft HEAD~1".to_string(),
-            exit_code: output.status.code(),
+            message: "Commit message cannot be empty".to_string(),
+            command: "reword".to_string(),
+            exit_code: None,
         });
     }
-    Ok(())
-}
 
-/// Checkout a branch
-pub async fn checkout(path: &Path, branch_name: &str) -> GitResult<()> {
-    // Detect if it's a remote branch (e.g., origin/feature/a2h)
-    // We check if it contains a slash and isn't a local branch name
-    // A better way is to check the remotes list, but for now common naming works
-    let remotes_output = execute_string(path, &["remote"]).await.unwrap_or_default();
-    let remotes: Vec<&str> = remotes_output.lines().collect();
+    let target = execute_string(path, &["rev-parse", "--verify", &format!("{}^{{commit}}", hash)]).await?;
+    let head = execute_string(path, &["rev-parse", "HEAD"]).await?;
 
-    let mut is_remote = false;
-    let mut short_name = String::new();
+    let signing = commit_signing_args(path, None).await;
 
-    for remote in remotes {
-        let prefix = format!("{}/", remote);
-        if branch_name.starts_with(&prefix) {
-            is_remote = true;
-            short_name = branch_name[prefix.len()..].to_string();
-            break;
+    if target == head {
+        let mut args = vec!["commit", "--amend", "--only", "--allow-empty", "-m", message];
+        args.extend(signing.iter().map(|s| s.as_str()));
+        let output = execute(path, &args).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if let Some(err) = signing_error(&stderr, "commit --amend --only", output.status.code()) {
+                return Err(err);
+            }
+            return Err(GitError {
+                message: stderr.to_string(),
+                command: "commit --amend --only".to_string(),
+                exit_code: output.status.code(),
+            });
         }
+        return Ok(());
     }
 
-    if is_remote {
-        // Check if local branch already exists
-        let locals_output = execute_string(path, &["branch"]).await.unwrap_or_default();
-        let local_exists = locals_output.lines().any(|l| {
-            let name = l.trim().trim_start_matches('*').trim();
-            name == short_name
+    // The commit must be reachable from HEAD, otherwise the rebase would not pick it up
+    let ancestor = execute(path, &["merge-base", "--is-ancestor", &target, "HEAD"]).await?;
+    if !ancestor.status.success() {
+        return Err(GitError {
+            message: format!("Commit {} is not part of the current branch", hash),
+            command: "reword".to_string(),
+            exit_code: ancestor.status.code(),
         });
+    }
 
-        if local_exists {
-            // Just switch to local
-            execute(path, &["checkout", &short_name]).await?;
-        } else {
-            // Create local tracking branch
-            // git checkout -b <short> --track <remote>/<short>
-            let output = execute(path, &["checkout", "-b", &short_name, "--track", branch_name]).await?;
-            if !output.status.success() {
-                // Fallback: regular checkout (detached HEAD)
-                execute(path, &["checkout", branch_name]).await?;
-            }
+    if is_rebase_or_merge_in_progress(path).await? {
+        return Err(GitError {
+            message: "A rebase or merge is already in progress".to_string(),
+            command: "reword".to_string(),
+            exit_code: None,
+        });
+    }
+
+    // Local changes are stashed here rather than with --autostash: a rebase that is
+    // refused before it starts (e.g. by a pre-rebase hook) strands git's own autostash
+    let before = execute_string(path, &["rev-parse", "--verify", "--quiet", "refs/stash"]).await.ok();
+    execute_string(path, &["stash", "push", "-m", "pinax: reword"]).await?;
+    let after = execute_string(path, &["rev-parse", "--verify", "--quiet", "refs/stash"]).await.ok();
+    let stashed = after.is_some() && after != before;
+
+    let result = reword_with_rebase(path, &target, &head, message, &signing).await;
+
+    if stashed {
+        let output = execute(path, &["stash", "pop", "--index"]).await?;
+        if !output.status.success() && result.is_ok() {
+            return Err(GitError {
+                message: "Commit reworded, but local changes could not be restored and are kept in the stash".to_string(),
+                command: "stash pop --index".to_string(),
+                exit_code: output.status.code(),
+            });
         }
+    }
+    result
+}
+
+/// Record an `amend!` commit for `target` and fold it in with an autosquash rebase
+///
+/// On failure the branch is put back at `head` with no rebase in progress.
+async fn reword_with_rebase(path: &Path, target: &str, head: &str, message: &str, signing: &[String]) -> GitResult<()> {
+    let subject = execute_string(path, &["log", "-1", "--format=%s", target]).await?;
+    let amend_message = format!("amend! {}\n\n{}", subject, message);
+
+    // --only without paths records an empty commit, leaving the index untouched
+    let output = execute(path, &["commit", "--only", "--allow-empty", "--no-verify", "-m", &amend_message]).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: "commit --only --allow-empty".to_string(),
+            exit_code: output.status.code(),
+        });
+    }
+
+    // Rebase onto the parent, or from the root when rewording the first commit.
+    // --rebase-merges recreates merges after the target instead of flattening them.
+    let is_root = execute(path, &["rev-parse", "--verify", "--quiet", &format!("{}^", target)]).await?;
+    let base = format!("{}^", target);
+    let mut args = vec![
+        "-c", "sequence.editor=:",
+        "-c", "core.editor=:",
+        "rebase", "-i", "--autosquash", "--rebase-merges",
+    ];
+    args.extend(signing.iter().map(|s| s.as_str()));
+    if is_root.status.success() {
+        args.push(&base);
     } else {
-        // Regular local checkout
-        execute(path, &["checkout", branch_name]).await?;
+        args.push("--root");
+    }
+
+    let output = execute(path, &args).await?;
+    if !output.status.success() {
+        // The amend! commit is empty, so a soft reset drops it without touching any changes
+        if is_rebase_or_merge_in_progress(path).await.unwrap_or(false) {
+            let _ = execute(path, &["rebase", "--abort"]).await;
+        }
+        let _ = execute(path, &["reset", "--soft", head]).await;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(err) = signing_error(&stderr, "rebase -i --autosquash --rebase-merges", output.status.code()) {
+            return Err(err);
+        }
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: "rebase -i --autosquash --rebase-merges".to_string(),
+            exit_code: output.status.code(),
+        });
     }
+    Ok(())
+}
 
+/// Undo the last commit (soft reset)
+pub async fn undo_commit(path: &Path) -> GitResult<()> {
+    ensure_writable(path, "reset --soft").await?;
+    let output = execute(path, &["reset", "--soft", "HEAD~1"]).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: "reset --soft HEAD~1".to_string(),
+            exit_code: output.status.code(),
+        });
+    }
     Ok(())
 }
 
 /// Create and checkout a new branch
 pub async fn create_branch(path: &Path, branch_name: &str) -> GitResult<()> {
-    execute(path, &["checkout", "-b", branch_name]).await?;
+    ensure_writable(path, "checkout -b").await?;
+    super::naming::ensure_new_branch_name(path, branch_name).await?;
+    execute_string(path, &["checkout", "-b", branch_name]).await?;
     Ok(())
 }
 
 /// Resolve a merge conflict by choosing a version
 pub async fn resolve_conflict(path: &Path, file_path: &str, resolution: &str) -> GitResult<()> {
+    ensure_writable(path, "checkout").await?;
     let arg = match resolution {
         "ours" => "--ours",
         "theirs" => "--theirs",
@@ -273,6 +475,7 @@ pub async fn get_file_diff(path: &Path, file_path: &str) -> GitResult<String> {
 
 /// Discard changes in a file
 pub async fn discard_changes(path: &Path, file_path: &str) -> GitResult<()> {
+    ensure_writable(path, "restore").await?;
     // Try to restore if it's tracked (staged or unstaged)
     let _ = execute(path, &["restore", "--staged", "--worktree", "--", file_path]).await;
     let _ = execute(path, &["restore", "--", file_path]).await;
@@ -285,6 +488,7 @@ pub async fn discard_changes(path: &Path, file_path: &str) -> GitResult<()> {
 
 /// Add a file pattern to .gitignore
 pub async fn add_to_gitignore(path: &Path, file_path: &str) -> GitResult<()> {
+    ensure_writable(path, "gitignore").await?;
     use std::fs::OpenOptions;
     use std::io::Write;
 
@@ -310,12 +514,12 @@ pub async fn add_to_gitignore(path: &Path, file_path: &str) -> GitResult<()> {
 
 /// Create a branch from a specific commit
 pub async fn create_branch_from_commit(path: &Path, branch_name: &str, hash: &str) -> GitResult<()> {
-    execute(path, &["branch", branch_name, hash]).await?;
-    Ok(())
+    super::branches::create_branch_at(path, branch_name, hash, None).await
 }
 
 /// Delete a branch
 pub async fn delete_branch(path: &Path, branch_name: &str, force: bool) -> GitResult<()> {
+    ensure_writable(path, "branch -d").await?;
     let flag = if force { "-D" } else { "-d" };
     let output = execute(path, &["branch", flag, branch_name]).await?;
     if !output.status.success() {
@@ -331,16 +535,26 @@ pub async fn delete_branch(path: &Path, branch_name: &str, force: bool) -> GitRe
 
 /// Checkout a specific commit (detached HEAD)
 pub async fn checkout_commit(path: &Path, hash: &str) -> GitResult<()> {
+    ensure_writable(path, "checkout").await?;
     execute(path, &["checkout", hash]).await?;
     Ok(())
 }
 
 /// Revert a specific commit
 pub async fn revert_commit(path: &Path, hash: &str) -> GitResult<()> {
+    ensure_writable(path, "revert").await?;
     // --no-edit avoids opening the editor
-    let output = execute(path, &["revert", "--no-edit", hash]).await?;
+    let signing = commit_signing_args(path, None).await;
+    let mut args = vec!["revert", "--no-edit"];
+    args.extend(signing.iter().map(|s| s.as_str()));
+    args.push(hash);
+
+    let output = execute(path, &args).await?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(err) = signing_error(&stderr, &format!("revert {}", hash), output.status.code()) {
+            return Err(err);
+        }
         return Err(GitError {
             message: stderr.to_string(),
             command: format!("revert {}", hash),
@@ -352,6 +566,7 @@ pub async fn revert_commit(path: &Path, hash: &str) -> GitResult<()> {
 
 /// Reset to a specific commit (mixed reset by default to preserve working tree)
 pub async fn reset_to_commit(path: &Path, hash: &str) -> GitResult<()> {
+    ensure_writable(path, "reset").await?;
     // Using mixed reset to keep changes in working directory but unstage them
     execute(path, &["reset", hash]).await?;
     Ok(())
@@ -359,9 +574,18 @@ pub async fn reset_to_commit(path: &Path, hash: &str) -> GitResult<()> {
 
 /// Cherry-pick a commit
 pub async fn cherry_pick_commit(path: &Path, hash: &str) -> GitResult<()> {
-    let output = execute(path, &["cherry-pick", hash]).await?;
+    ensure_writable(path, "cherry-pick").await?;
+    let signing = commit_signing_args(path, None).await;
+    let mut args = vec!["cherry-pick"];
+    args.extend(signing.iter().map(|s| s.as_str()));
+    args.push(hash);
+
+    let output = execute(path, &args).await?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(err) = signing_error(&stderr, &format!("cherry-pick {}", hash), output.status.code()) {
+            return Err(err);
+        }
         return Err(GitError {
             message: stderr.to_string(),
             command: format!("cherry-pick {}", hash),
@@ -370,6 +594,31 @@ pub async fn cherry_pick_commit(path: &Path, hash: &str) -> GitResult<()> {
     }
     Ok(())
 }
+
+/// Create an annotated tag, signed according to `tag.gpgsign` unless `sign` is set
+pub async fn create_tag(path: &Path, name: &str, target: &str, message: &str, sign: Option<bool>) -> GitResult<()> {
+    ensure_writable(path, "tag").await?;
+    let signing = tag_signing_args(path, sign).await;
+    let mut args = vec!["tag", "-a", "-m", message];
+    args.extend(signing.iter().map(|s| s.as_str()));
+    args.push(name);
+    args.push(target);
+
+    let output = execute(path, &args).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(err) = signing_error(&stderr, &format!("tag {}", name), output.status.code()) {
+            return Err(err);
+        }
+        return Err(GitError {
+            message: stderr.to_string(),
+            command: format!("tag {}", name),
+            exit_code: output.status.code(),
+        });
+    }
+    Ok(())
+}
+
 /// Initialize a new Git repository
 pub async fn init(path: &Path) -> GitResult<()> {
     // Ensure parent directory exists
@@ -470,7 +719,7 @@ pub async fn clone(url: &str, path: &Path) -> GitResult<()> {
 
 /// Check if there's a rebase or merge in progress
 pub async fn is_rebase_or_merge_in_progress(path: &Path) -> GitResult<bool> {
-    let git_dir = path.join(".git");
+    let git_dir = git_dir(path).await?;
     let rebase_apply = git_dir.join("rebase-apply");
     let rebase_merge = git_dir.join("rebase-merge");
     let merge_head = git_dir.join("MERGE_HEAD");
@@ -482,7 +731,7 @@ pub async fn is_rebase_or_merge_in_progress(path: &Path) -> GitResult<bool> {
 /// Continue a rebase or merge after resolving conflicts
 pub async fn continue_rebase_or_merge(path: &Path) -> GitResult<()> {
     // Check if we're in a rebase
-    let git_dir = path.join(".git");
+    let git_dir = git_dir(path).await?;
     let rebase_apply = git_dir.join("rebase-apply");
     let rebase_merge = git_dir.join("rebase-merge");
 
@@ -514,7 +763,7 @@ pub async fn continue_rebase_or_merge(path: &Path) -> GitResult<()> {
 
 /// Abort a rebase or merge
 pub async fn abort_rebase_or_merge(path: &Path) -> GitResult<()> {
-    let git_dir = path.join(".git");
+    let git_dir = git_dir(path).await?;
     let rebase_apply = git_dir.join("rebase-apply");
     let rebase_merge = git_dir.join("rebase-merge");
 
@@ -0,0 +1,223 @@
+//! Commit search
+//!
+//! Searches history by message, author, hash prefix, path and content (pickaxe).
+//! Results are produced incrementally as `git log` walks the history.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute_lines_until, execute_string, execute_with_input, GitError, GitResult};
+use super::history::{parse_log_line, LOG_FORMAT};
+use super::types::CommitInfo;
+
+/// Results returned by a search without an explicit limit
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+/// What to look for in the history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitQuery {
+    /// Text matched against the commit message
+    pub message: Option<String>,
+    /// Treat `message` and `content` as extended regular expressions
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Matched against author name and email
+    pub author: Option<String>,
+    /// At least four hex digits; looked up in the object database, so commits on
+    /// any branch are found regardless of `all_refs`
+    pub hash_prefix: Option<String>,
+    /// Only commits touching this path (file or directory)
+    pub path: Option<String>,
+    /// Commits that add or remove this text (`-S`), or lines matching it with `regex` (`-G`)
+    pub content: Option<String>,
+    /// Search every branch and tag instead of HEAD only
+    pub all_refs: bool,
+    pub skip: usize,
+    pub limit: Option<usize>,
+}
+
+/// Searches that were cancelled and should stop
+fn cancelled() -> &'static Mutex<HashSet<String>> {
+    static CANCELLED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    CANCELLED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Stop a streamed search, including one that has not started yet
+pub fn cancel_commit_search(search_id: &str) {
+    if let Ok(mut set) = cancelled().lock() {
+        set.insert(search_id.to_string());
+    }
+}
+
+fn is_cancelled(search_id: &str) -> bool {
+    cancelled().lock().map(|set| set.contains(search_id)).unwrap_or(false)
+}
+
+/// Build the `git log` arguments for a query
+///
+/// `commits` restricts the output to those commits instead of walking the history.
+fn query_args(query: &CommitQuery, commits: Option<Vec<String>>) -> Vec<String> {
+    let mut args = vec![
+        "log".to_string(),
+        "--date=iso-strict".to_string(),
+        // Signatures are verified on demand for the rows shown
+        format!("--format={}", LOG_FORMAT),
+    ];
+
+    if let Some(commits) = commits {
+        args.push("--no-walk=sorted".to_string());
+        args.extend(commits);
+    } else if query.all_refs {
+        // Deleted branches kept for restore are not part of the history
+        args.push("--exclude=refs/pinax/*".to_string());
+        args.push("--all".to_string());
+    }
+    if !query.case_sensitive {
+        args.push("--regexp-ignore-case".to_string());
+    }
+    if query.regex {
+        args.push("--extended-regexp".to_string());
+    } else {
+        args.push("--fixed-strings".to_string());
+    }
+
+    if let Some(message) = query.message.as_deref().filter(|m| !m.is_empty()) {
+        args.push(format!("--grep={}", message));
+    }
+    if let Some(author) = query.author.as_deref().filter(|a| !a.is_empty()) {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(content) = query.content.as_deref().filter(|c| !c.is_empty()) {
+        if query.regex {
+            args.push(format!("-G{}", content));
+        } else {
+            args.push(format!("-S{}", content));
+        }
+    }
+
+    args.push("--".to_string());
+    if let Some(path) = query.path.as_deref().filter(|p| !p.is_empty()) {
+        args.push(path.to_string());
+    }
+
+    args
+}
+
+/// Full hashes of the commits whose hash starts with `prefix`
+async fn resolve_hash_prefix(repo_path: &Path, prefix: &str) -> GitResult<Vec<String>> {
+    if prefix.len() < 4 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(GitError {
+            message: format!("'{}' is not a commit hash; enter at least 4 hex digits", prefix),
+            command: "rev-parse --disambiguate".to_string(),
+            exit_code: None,
+        });
+    }
+
+    // Lists every object with the prefix, including trees and blobs
+    let objects = execute_string(repo_path, &["rev-parse", &format!("--disambiguate={}", prefix)]).await?;
+    if objects.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let args = ["cat-file", "--batch-check=%(objecttype) %(objectname)"];
+    let types = execute_with_input(repo_path, &args, &format!("{}\n", objects)).await?;
+    Ok(types
+        .lines()
+        .filter_map(|line| line.strip_prefix("commit "))
+        .map(|hash| hash.to_string())
+        .collect())
+}
+
+/// Run a query, handing matching commits to `on_match` in history order
+///
+/// Stops after `skip + limit` matches, when `on_match` returns `false`, or as soon
+/// as `should_stop` returns `true`.
+async fn search_commits_until<S, F>(repo_path: &Path, query: &CommitQuery, should_stop: S, mut on_match: F) -> GitResult<()>
+where
+    S: Fn() -> bool,
+    F: FnMut(CommitInfo) -> bool,
+{
+    let commits = match query.hash_prefix.as_deref().filter(|p| !p.is_empty()) {
+        Some(prefix) => {
+            let commits = resolve_hash_prefix(repo_path, &prefix.to_lowercase()).await?;
+            if commits.is_empty() {
+                return Ok(());
+            }
+            Some(commits)
+        }
+        None => None,
+    };
+    let args = query_args(query, commits);
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let mut seen = 0;
+    let mut sent = 0;
+    execute_lines_until(repo_path, &args, should_stop, |line| {
+        let Some(commit) = parse_log_line(line) else { return true };
+
+        seen += 1;
+        if seen <= query.skip {
+            return true;
+        }
+        sent += 1;
+        on_match(commit) && sent < limit
+    })
+    .await
+}
+
+/// Run a query, handing matching commits to `on_match` in history order
+///
+/// Stops after `skip + limit` matches or when `on_match` returns `false`.
+pub async fn search_commits_with<F>(repo_path: &Path, query: &CommitQuery, on_match: F) -> GitResult<()>
+where
+    F: FnMut(CommitInfo) -> bool,
+{
+    search_commits_until(repo_path, query, || false, on_match).await
+}
+
+/// Run a query and collect one page of results
+pub async fn search_commits(repo_path: &Path, query: &CommitQuery) -> GitResult<Vec<CommitInfo>> {
+    let mut commits = Vec::new();
+    search_commits_with(repo_path, query, |commit| {
+        commits.push(commit);
+        true
+    })
+    .await?;
+    Ok(commits)
+}
+
+/// Run a query, delivering results in batches of `batch_size` until done or cancelled
+///
+/// `on_batch` receives each batch and whether it is the last one.
+pub async fn search_commits_batched<F>(
+    repo_path: &Path,
+    search_id: &str,
+    query: &CommitQuery,
+    batch_size: usize,
+    mut on_batch: F,
+) -> GitResult<()>
+where
+    F: FnMut(Vec<CommitInfo>, bool),
+{
+    // Not cleared here: a cancel may arrive before the run starts
+    let mut batch = Vec::new();
+    let result = search_commits_until(repo_path, query, || is_cancelled(search_id), |commit| {
+        batch.push(commit);
+        if batch.len() >= batch_size.max(1) {
+            on_batch(std::mem::take(&mut batch), false);
+        }
+        true
+    })
+    .await;
+
+    on_batch(batch, true);
+    if let Ok(mut set) = cancelled().lock() {
+        set.remove(search_id);
+    }
+    result
+}
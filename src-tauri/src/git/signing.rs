@@ -0,0 +1,177 @@
+//! Git signing module
+//!
+//! Commit and tag signing with OpenPGP, SSH or X.509 keys, driven by git config.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::executor::{execute_string, GitError, GitResult};
+
+/// Signature backend selected by `gpg.format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    Openpgp,
+    Ssh,
+    X509,
+}
+
+/// Effective signing configuration of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningConfig {
+    pub sign_commits: bool,
+    pub sign_tags: bool,
+    pub format: SignatureFormat,
+    pub signing_key: Option<String>,
+}
+
+/// Verification result of a commit signature, from `%G?`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureStatus {
+    Good,
+    Bad,
+    Unknown,
+    Expired,
+    Revoked,
+    #[default]
+    None,
+}
+
+impl SignatureStatus {
+    /// Map the `%G?` placeholder of `git log`
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "G" => SignatureStatus::Good,
+            "B" => SignatureStatus::Bad,
+            "U" | "E" => SignatureStatus::Unknown,
+            "X" | "Y" => SignatureStatus::Expired,
+            "R" => SignatureStatus::Revoked,
+            _ => SignatureStatus::None,
+        }
+    }
+}
+
+/// Read a boolean config value, treating missing keys as `false`
+async fn config_bool(repo_path: &Path, key: &str) -> bool {
+    execute_string(repo_path, &["config", "--type=bool", key])
+        .await
+        .map(|v| v.trim() == "true")
+        .unwrap_or(false)
+}
+
+/// Load `commit.gpgsign`, `tag.gpgsign`, `gpg.format` and `user.signingkey`
+pub async fn get_signing_config(repo_path: &Path) -> SigningConfig {
+    let format = match execute_string(repo_path, &["config", "gpg.format"]).await {
+        Ok(f) if f.trim() == "ssh" => SignatureFormat::Ssh,
+        Ok(f) if f.trim() == "x509" => SignatureFormat::X509,
+        _ => SignatureFormat::Openpgp,
+    };
+
+    let signing_key = execute_string(repo_path, &["config", "user.signingkey"])
+        .await
+        .ok()
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty());
+
+    SigningConfig {
+        sign_commits: config_bool(repo_path, "commit.gpgsign").await,
+        sign_tags: config_bool(repo_path, "tag.gpgsign").await,
+        format,
+        signing_key,
+    }
+}
+
+/// Arguments for commit-creating commands (`commit`, `revert`, `cherry-pick`)
+///
+/// `sign` overrides `commit.gpgsign` when set.
+pub async fn commit_signing_args(repo_path: &Path, sign: Option<bool>) -> Vec<String> {
+    let config = get_signing_config(repo_path).await;
+    match sign.unwrap_or(config.sign_commits) {
+        true => match config.signing_key {
+            Some(key) => vec![format!("--gpg-sign={}", key)],
+            None => vec!["--gpg-sign".to_string()],
+        },
+        false => vec!["--no-gpg-sign".to_string()],
+    }
+}
+
+/// Arguments for `git tag`, honoring `tag.gpgsign` unless `sign` is set
+pub async fn tag_signing_args(repo_path: &Path, sign: Option<bool>) -> Vec<String> {
+    let config = get_signing_config(repo_path).await;
+    match sign.unwrap_or(config.sign_tags) {
+        true => match config.signing_key {
+            Some(key) => vec!["-u".to_string(), key],
+            None => vec!["-s".to_string()],
+        },
+        false => vec!["--no-sign".to_string()],
+    }
+}
+
+/// Verification result of one commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignature {
+    pub hash: String,
+    pub signature: SignatureStatus,
+    pub signer: Option<String>,
+}
+
+/// Verify the signatures of `hashes` with one `git log` call
+///
+/// Listings leave signatures out because verifying runs gpg for every signed commit;
+/// this checks just the rows on screen. Results are keyed by full commit hash.
+pub async fn verify_commit_signatures(repo_path: &Path, hashes: &[String]) -> GitResult<HashMap<String, CommitSignature>> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut args = vec!["log", "--no-walk=unsorted", "--format=%H%x09%G?%x09%GS"];
+    args.extend(hashes.iter().map(|h| h.as_str()));
+    args.push("--");
+    let output = execute_string(repo_path, &args).await?;
+
+    Ok(output
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(3, '\t');
+            let hash = parts.next()?.to_string();
+            let signature = SignatureStatus::from_code(parts.next()?);
+            let signer = parts.next().map(str::to_string).filter(|s| !s.is_empty());
+            Some((hash.clone(), CommitSignature { hash, signature, signer }))
+        })
+        .collect())
+}
+
+/// Turn signing failures into an actionable error
+///
+/// Returns `None` when `stderr` does not look like a signing problem.
+pub fn signing_error(stderr: &str, command: &str, exit_code: Option<i32>) -> Option<GitError> {
+    let lower = stderr.to_lowercase();
+
+    let hint = if lower.contains("pinentry") || lower.contains("inappropriate ioctl for device") {
+        "no pinentry program is available to ask for the key passphrase. Configure a graphical pinentry in gpg-agent.conf"
+    } else if lower.contains("no secret key") || lower.contains("secret key not available") {
+        "the configured signing key was not found in the keyring. Check user.signingkey"
+    } else if lower.contains("could not open a connection to your authentication agent")
+        || lower.contains("agent refused operation")
+        || lower.contains("communication with agent failed")
+    {
+        "the signing agent is not running or refused the request. Start gpg-agent or ssh-agent and unlock the key"
+    } else if lower.contains("ssh-keygen") || lower.contains("load key") {
+        "ssh-keygen could not sign with the configured key. Check user.signingkey and gpg.ssh.program"
+    } else if lower.contains("gpgsm") {
+        "gpgsm could not sign with the configured X.509 certificate. Check user.signingkey and gpg.x509.program"
+    } else if lower.contains("gpg failed to sign the data") || lower.contains("failed to sign") {
+        "the signing program exited with an error"
+    } else {
+        return None;
+    };
+
+    Some(GitError {
+        message: format!("Signing failed: {}.\n{}", hint, stderr.trim()),
+        command: command.to_string(),
+        exit_code,
+    })
+}
@@ -4,7 +4,7 @@
 
 use std::path::Path;
 
-use super::executor::{execute_string, GitResult};
+use super::executor::{execute_string, is_bare_repository, GitResult};
 use super::types::{FileChange, FileStatus, RepositoryStatus};
 
 /// Get the status of a Git repository
@@ -15,7 +15,17 @@ pub async fn get_status(repo_path: &Path) -> GitResult<RepositoryStatus> {
     status.branch = get_current_branch(repo_path).await?;
 
     // Get porcelain status
-    let porcelain_output = execute_string(repo_path, &["status", "--porcelain=v1"]).await?;
+    let porcelain_output = match execute_string(repo_path, &["status", "--porcelain=v1"]).await {
+        Ok(output) => output,
+        Err(e) => {
+            // Bare repositories have no working tree, so they are browsed read-only
+            if is_bare_repository(repo_path).await {
+                status.read_only = true;
+                return Ok(status);
+            }
+            return Err(e);
+        }
+    };
 
     // Parse status output
     if porcelain_output.is_empty() {
@@ -115,7 +125,7 @@ async fn get_current_branch(repo_path: &Path) -> GitResult<String> {
 }
 
 /// Get ahead/behind counts from upstream
-async fn get_tracking_info(repo_path: &Path) -> GitResult<(u32, u32)> {
+pub async fn get_tracking_info(repo_path: &Path) -> GitResult<(u32, u32)> {
     let output = execute_string(
         repo_path,
         &["rev-list", "--left-right", "--count", "@{upstream}...HEAD"],
@@ -146,30 +156,21 @@ fn parse_porcelain_status(output: &str, status: &mut RepositoryStatus) {
         // Check for conflicts first (Unmerged)
         if index_status == 'U' || worktree_status == 'U' || (index_status == 'A' && worktree_status == 'A') || (index_status == 'D' && worktree_status == 'D') {
             status.is_clean = false;
-            status.conflicts.push(FileChange {
-                path: path.clone(),
-                status: FileStatus::Conflicted,
-            });
+            status.conflicts.push(FileChange::new(path.clone(), FileStatus::Conflicted));
             continue;
         }
 
         // Staged changes (index)
         if index_status != ' ' && index_status != '?' {
             if let Some(file_status) = char_to_file_status(index_status) {
-                status.staged.push(FileChange {
-                    path: path.clone(),
-                    status: file_status,
-                });
+                status.staged.push(FileChange::new(path.clone(), file_status));
             }
         }
 
         // Unstaged changes (worktree)
         if worktree_status != ' ' && worktree_status != '?' {
             if let Some(file_status) = char_to_file_status(worktree_status) {
-                status.unstaged.push(FileChange {
-                    path: path.clone(),
-                    status: file_status,
-                });
+                status.unstaged.push(FileChange::new(path.clone(), file_status));
             }
         }
 
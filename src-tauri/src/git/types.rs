@@ -4,6 +4,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::message::LintRules;
+use super::signing::SignatureStatus;
+
 /// Status of a Git repository
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepositoryStatus {
@@ -15,6 +18,9 @@ pub struct RepositoryStatus {
     pub unstaged: Vec<FileChange>,
     pub untracked: Vec<String>,
     pub conflicts: Vec<FileChange>,
+    /// Bare repository: history can be browsed but write operations are rejected
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 impl Default for RepositoryStatus {
@@ -28,6 +34,7 @@ impl Default for RepositoryStatus {
             unstaged: Vec::new(),
             untracked: Vec::new(),
             conflicts: Vec::new(),
+            read_only: false,
         }
     }
 }
@@ -37,6 +44,31 @@ impl Default for RepositoryStatus {
 pub struct FileChange {
     pub path: String,
     pub status: FileStatus,
+    /// Source path of a rename or copy
+    #[serde(default)]
+    pub old_path: Option<String>,
+    /// Similarity percentage of a rename or copy
+    #[serde(default)]
+    pub similarity: Option<u8>,
+    /// Lines added; `None` for binary files or when not computed
+    #[serde(default)]
+    pub additions: Option<u32>,
+    /// Lines deleted; `None` for binary files or when not computed
+    #[serde(default)]
+    pub deletions: Option<u32>,
+}
+
+impl FileChange {
+    pub fn new(path: String, status: FileStatus) -> Self {
+        Self {
+            path,
+            status,
+            old_path: None,
+            similarity: None,
+            additions: None,
+            deletions: None,
+        }
+    }
 }
 
 /// Status of a file change
@@ -59,6 +91,21 @@ pub struct Branch {
     pub is_remote: bool,
     pub upstream: Option<String>,
     pub track: Option<String>,
+    /// Committer date of the tip, ISO 8601
+    #[serde(default)]
+    pub last_commit_date: Option<String>,
+    #[serde(default)]
+    pub last_commit_author: Option<String>,
+    #[serde(default)]
+    pub ahead: u32,
+    #[serde(default)]
+    pub behind: u32,
+    /// The upstream is configured but no longer exists on the remote
+    #[serde(default)]
+    pub upstream_gone: bool,
+    /// The tip is reachable from the default branch
+    #[serde(default)]
+    pub merged: bool,
 }
 
 /// Information about a commit
@@ -70,4 +117,50 @@ pub struct CommitInfo {
     pub author: String,
     pub email: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub signature: SignatureStatus,
+    #[serde(default)]
+    pub signer: Option<String>,
+}
+
+/// Options controlling how a commit is created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitOptions {
+    /// Replace HEAD instead of creating a new commit
+    pub amend: bool,
+    /// Create the commit even if nothing is staged
+    pub allow_empty: bool,
+    /// Skip the pre-commit and commit-msg hooks
+    pub no_verify: bool,
+    /// Add a Signed-off-by trailer
+    pub signoff: bool,
+    /// Override the author, in `Name <email>` form
+    pub author: Option<String>,
+    /// Override the author date
+    pub date: Option<String>,
+    /// Create a `fixup!` commit targeting this hash
+    pub fixup: Option<String>,
+    /// Create a `squash!` commit targeting this hash
+    pub squash: Option<String>,
+    /// Sign the commit, overriding `commit.gpgsign` when set
+    pub sign: Option<bool>,
+    /// Identities appended as `Co-authored-by` trailers
+    pub co_authors: Vec<String>,
+    /// Reject the commit if the message breaks any of these rules
+    pub lint: Option<LintRules>,
+}
+
+/// Layout of a repository on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepositoryKind {
+    #[default]
+    Normal,
+    /// No working tree; only history can be browsed
+    Bare,
+    /// A linked worktree created with `git worktree add`
+    Worktree,
+    /// A submodule checkout inside another repository
+    Submodule,
 }
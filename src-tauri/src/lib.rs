@@ -2,6 +2,7 @@
 //!
 //! Core Rust backend for the Tauri application.
 
+mod cache;
 mod git;
 mod repository;
 mod workspace;
@@ -19,13 +20,51 @@ use workspace::Workspace;
 
 /// Scan a directory for Git repositories
 #[tauri::command]
-async fn scan_repositories(path: String) -> Result<Vec<Repository>, String> {
-    repository::scan_repositories(Path::new(&path)).await
+async fn scan_repositories(path: String, options: Option<repository::ScanOptions>) -> Result<Vec<Repository>, String> {
+    let options = options.unwrap_or_default();
+    repository::scan_repositories_with(Path::new(&path), &options, |_| {}).await
+}
+
+/// Payload of the `repository-found` event
+#[derive(Clone, serde::Serialize)]
+struct RepositoryFoundEvent {
+    scan_id: String,
+    repository: Repository,
+}
+
+/// Scan a directory, emitting a `repository-found` event for each repository as it is discovered
+#[tauri::command]
+async fn scan_repositories_stream(
+    app: tauri::AppHandle,
+    path: String,
+    scan_id: String,
+    options: Option<repository::ScanOptions>,
+) -> Result<Vec<Repository>, String> {
+    use tauri::Emitter;
+
+    let options = options.unwrap_or_default();
+    repository::scan_repositories_with(Path::new(&path), &options, |repo| {
+        let _ = app.emit(
+            "repository-found",
+            RepositoryFoundEvent {
+                scan_id: scan_id.clone(),
+                repository: repo.clone(),
+            },
+        );
+    })
+    .await
 }
 
 #[tauri::command]
 async fn get_repository_info(path: String) -> Result<Repository, String> {
-    repository::get_repository_info(Path::new(&path)).await
+    let repo = repository::get_repository_info(Path::new(&path)).await?;
+    Ok(repository::summarize_repository(repo, false).await)
+}
+
+/// Get repositories with their last commit and summary metadata, most recent activity first
+#[tauri::command]
+async fn get_repository_summaries(paths: Vec<String>, force: Option<bool>) -> Result<Vec<Repository>, String> {
+    Ok(repository::summarize_repositories(paths, force.unwrap_or(false)).await)
 }
 
 /// Get the status of a Git repository
@@ -44,26 +83,192 @@ async fn list_branches(path: String) -> Result<Vec<Branch>, String> {
         .map_err(|e| e.to_string())
 }
 
-/// Get commit history
+/// Get commit history, 50 commits at a time unless a limit is given
+#[tauri::command]
+async fn get_git_history(path: String, skip: Option<usize>, limit: Option<usize>) -> Result<Vec<git::CommitInfo>, String> {
+    cache::get_cached_history(Path::new(&path), skip.unwrap_or(0), limit.unwrap_or(50))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Add commits reachable from new refs to the history cache
+#[tauri::command]
+async fn index_repository_history(path: String) -> Result<usize, String> {
+    cache::index_repository_history(Path::new(&path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn clear_repository_cache(path: String) -> Result<(), String> {
+    cache::clear_repository_cache(Path::new(&path)).await
+}
+
+/// Payload of the `commit-search-results` event
+#[derive(Clone, serde::Serialize)]
+struct CommitSearchEvent {
+    search_id: String,
+    commits: Vec<git::CommitInfo>,
+    done: bool,
+}
+
+/// Search commits and return one page of results
+#[tauri::command]
+async fn search_commits(path: String, query: git::CommitQuery) -> Result<Vec<git::CommitInfo>, String> {
+    git::search_commits(Path::new(&path), &query)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Search commits, emitting `commit-search-results` events as batches of matches are found
 #[tauri::command]
-async fn get_git_history(path: String) -> Result<Vec<git::CommitInfo>, String> {
-    git::get_history(Path::new(&path))
+async fn search_commits_stream(
+    app: tauri::AppHandle,
+    path: String,
+    search_id: String,
+    query: git::CommitQuery,
+) -> Result<(), String> {
+    use tauri::Emitter;
+
+    git::search_commits_batched(Path::new(&path), &search_id, &query, 50, |commits, done| {
+        let _ = app.emit(
+            "commit-search-results",
+            CommitSearchEvent {
+                search_id: search_id.clone(),
+                commits,
+                done,
+            },
+        );
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn cancel_commit_search(search_id: String) {
+    git::cancel_commit_search(&search_id);
+}
+
+/// Get files changed in a commit, against `parent` (1-based) for merges
+#[tauri::command]
+async fn get_commit_files(path: String, hash: String, parent: Option<usize>) -> Result<Vec<git::FileChange>, String> {
+    cache::get_cached_commit_files(Path::new(&path), &hash, parent)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Get files changed in a commit
+/// Get the commits that changed a file, following renames
 #[tauri::command]
-async fn get_commit_files(path: String, hash: String) -> Result<Vec<git::FileChange>, String> {
-    git::get_commit_files(Path::new(&path), &hash)
+async fn get_file_history(
+    path: String,
+    file_path: String,
+    skip: Option<usize>,
+    limit: Option<usize>,
+    include_diff: Option<bool>,
+) -> Result<Vec<git::FileHistoryEntry>, String> {
+    git::get_file_history(Path::new(&path), &file_path, skip.unwrap_or(0), limit, include_diff.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the diff of a file in one commit, showing renames from old_path
+#[tauri::command]
+async fn get_file_diff_in_commit(path: String, hash: String, file_path: String, old_path: Option<String>) -> Result<String, String> {
+    git::get_file_diff_in_commit(Path::new(&path), &hash, &file_path, old_path.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Read a file as it was at a revision
+#[tauri::command]
+async fn get_file_at_revision(path: String, revision: String, file_path: String) -> Result<git::FileAtRevision, String> {
+    git::get_file_at_revision(Path::new(&path), &revision, &file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Blame a file and return its line ranges in order
+#[tauri::command]
+async fn blame_file(path: String, file_path: String, options: Option<git::BlameOptions>) -> Result<Vec<git::BlameRange>, String> {
+    git::blame_file(Path::new(&path), &file_path, &options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Payload of the `blame-ranges` event
+#[derive(Clone, serde::Serialize)]
+struct BlameEvent {
+    blame_id: String,
+    ranges: Vec<git::BlameRange>,
+    done: bool,
+}
+
+/// Blame a file, emitting `blame-ranges` events as git reports line ranges
+#[tauri::command]
+async fn blame_file_stream(
+    app: tauri::AppHandle,
+    path: String,
+    file_path: String,
+    blame_id: String,
+    options: Option<git::BlameOptions>,
+) -> Result<(), String> {
+    use tauri::Emitter;
+
+    // Small batches keep the first lines on screen quickly without flooding the event bus
+    let options = options.unwrap_or_default();
+    git::blame_file_batched(Path::new(&path), &blame_id, &file_path, &options, 20, |ranges, done| {
+        let _ = app.emit(
+            "blame-ranges",
+            BlameEvent {
+                blame_id: blame_id.clone(),
+                ranges,
+                done,
+            },
+        );
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn cancel_blame(blame_id: String) {
+    git::cancel_blame(&blame_id);
+}
+
+/// Compare two refs: commits unique to each side and files changed since the merge base
+#[tauri::command]
+async fn compare_refs(path: String, base: String, target: String, limit: Option<usize>) -> Result<git::CompareResult, String> {
+    git::compare_refs(Path::new(&path), &base, &target, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the diff of a file between the merge base of two refs and the target
+#[tauri::command]
+async fn get_compare_file_diff(path: String, base: String, target: String, file_path: String) -> Result<String, String> {
+    git::get_compare_file_diff(Path::new(&path), &base, &target, &file_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Check whether merging target into base would conflict
+#[tauri::command]
+async fn preview_merge(path: String, base: String, target: String) -> Result<git::MergePreview, String> {
+    git::preview_merge(Path::new(&path), &base, &target)
         .await
         .map_err(|e| e.to_string())
 }
 
 /// Get diff for a file in a specific commit
 #[tauri::command]
-async fn get_commit_file_diff(path: String, hash: String, file_path: String) -> Result<String, String> {
-    git::get_commit_file_diff(Path::new(&path), &hash, &file_path)
+async fn get_commit_file_diff(
+    path: String,
+    hash: String,
+    file_path: String,
+    old_path: Option<String>,
+    parent: Option<usize>,
+) -> Result<String, String> {
+    git::get_commit_file_diff(Path::new(&path), &hash, &file_path, old_path.as_deref(), parent)
         .await
         .map_err(|e| e.to_string())
 }
@@ -92,6 +297,148 @@ async fn delete_workspace(id: String) -> Result<(), String> {
     workspace::delete_workspace(id).await
 }
 
+/// Rename a workspace
+#[tauri::command]
+async fn rename_workspace(workspace_id: String, name: String) -> Result<(), String> {
+    workspace::rename_workspace(&workspace_id, name).await
+}
+
+/// Set or clear the color of a workspace
+#[tauri::command]
+async fn set_workspace_color(workspace_id: String, color: Option<String>) -> Result<(), String> {
+    workspace::set_workspace_color(&workspace_id, color).await
+}
+
+/// Remove a repository from a workspace
+#[tauri::command]
+async fn remove_repository_from_workspace(workspace_id: String, repo_path: String) -> Result<(), String> {
+    workspace::remove_repository_from_workspace(&workspace_id, &repo_path).await
+}
+
+/// Reorder the repositories of a workspace
+#[tauri::command]
+async fn reorder_workspace_repositories(workspace_id: String, order: Vec<String>) -> Result<(), String> {
+    workspace::reorder_repositories(&workspace_id, order).await
+}
+
+/// Move a repository from one workspace to another
+#[tauri::command]
+async fn move_repository_between_workspaces(
+    from_workspace_id: String,
+    to_workspace_id: String,
+    repo_path: String,
+    index: Option<usize>,
+) -> Result<(), String> {
+    workspace::move_repository_between_workspaces(&from_workspace_id, &to_workspace_id, &repo_path, index).await
+}
+
+/// Set or clear the display alias of a repository in a workspace
+#[tauri::command]
+async fn set_repository_alias(workspace_id: String, repo_path: String, alias: Option<String>) -> Result<(), String> {
+    workspace::set_repository_alias(&workspace_id, &repo_path, alias).await
+}
+
+/// Pin or unpin a repository in a workspace
+#[tauri::command]
+async fn set_repository_pinned(workspace_id: String, repo_path: String, pinned: bool) -> Result<(), String> {
+    workspace::set_repository_pinned(&workspace_id, &repo_path, pinned).await
+}
+
+/// Export a workspace to a portable file
+#[tauri::command]
+async fn export_workspace(workspace_id: String, file: String) -> Result<(), String> {
+    workspace::export_workspace(&workspace_id, Path::new(&file)).await
+}
+
+/// Preview how a workspace file maps onto local clones under a root directory
+#[tauri::command]
+async fn plan_workspace_import(file: String, root: String) -> Result<workspace::ImportPlan, String> {
+    workspace::plan_workspace_import(Path::new(&file), Path::new(&root)).await
+}
+
+/// Import a workspace file, optionally cloning missing repositories
+#[tauri::command]
+async fn import_workspace(file: String, root: String, clone_missing: bool) -> Result<workspace::ImportResult, String> {
+    workspace::import_workspace(Path::new(&file), Path::new(&root), clone_missing).await
+}
+
+/// Set or clear the discovery roots of a workspace
+#[tauri::command]
+async fn set_workspace_discovery(workspace_id: String, discovery: Option<workspace::DiscoveryConfig>) -> Result<(), String> {
+    workspace::set_workspace_discovery(&workspace_id, discovery).await
+}
+
+/// Rescan the discovery roots of a workspace now
+#[tauri::command]
+async fn rescan_workspace(workspace_id: String) -> Result<workspace::DiscoveryResult, String> {
+    workspace::rescan_workspace(&workspace_id).await
+}
+
+/// Set or clear the identity profile of a workspace
+#[tauri::command]
+async fn set_workspace_identity(workspace_id: String, identity: Option<workspace::IdentityProfile>) -> Result<(), String> {
+    workspace::set_workspace_identity(&workspace_id, identity).await
+}
+
+/// Check that every repository in a workspace uses its identity profile
+#[tauri::command]
+async fn check_workspace_identity(workspace_id: String) -> Result<Vec<workspace::IdentityCheck>, String> {
+    workspace::check_workspace_identity(&workspace_id).await
+}
+
+/// Apply the workspace identity profile to the local config of its repositories
+#[tauri::command]
+async fn apply_workspace_identity(workspace_id: String, paths: Option<Vec<String>>) -> Result<Vec<workspace::IdentityCheck>, String> {
+    workspace::apply_workspace_identity(&workspace_id, paths).await
+}
+
+/// Set or clear the background fetch settings of a workspace
+#[tauri::command]
+async fn set_workspace_auto_fetch(
+    workspace_id: String,
+    config: Option<workspace::AutoFetchConfig>,
+) -> Result<(), String> {
+    workspace::set_workspace_auto_fetch(&workspace_id, config).await
+}
+
+/// Opt a repository of a workspace in or out of background fetches
+#[tauri::command]
+async fn set_repository_auto_fetch(workspace_id: String, repo_path: String, enabled: bool) -> Result<(), String> {
+    workspace::set_repository_auto_fetch(&workspace_id, &repo_path, enabled).await
+}
+
+/// Tell the auto-fetch scheduler which repositories are open
+#[tauri::command]
+fn set_auto_fetch_open_repositories(paths: Vec<String>) {
+    workspace::set_open_repositories(paths);
+}
+
+/// Tell the auto-fetch scheduler whether the machine is online
+#[tauri::command]
+fn set_network_online(online: bool) {
+    workspace::set_network_online(online);
+}
+
+/// Run a batch operation on every repository of a workspace
+#[tauri::command]
+async fn run_workspace_batch(
+    workspace_id: String,
+    operation: workspace::BatchOperation,
+    concurrency: Option<usize>,
+) -> Result<Vec<workspace::BatchResult>, String> {
+    workspace::run_workspace_batch(&workspace_id, operation, concurrency).await
+}
+
+/// Run a batch operation on the given repositories
+#[tauri::command]
+async fn run_repositories_batch(
+    paths: Vec<String>,
+    operation: workspace::BatchOperation,
+    concurrency: Option<usize>,
+) -> Result<Vec<workspace::BatchResult>, String> {
+    workspace::run_batch(paths, operation, concurrency).await
+}
+
 // ============== Git Operations Commands ==============
 
 #[tauri::command]
@@ -110,13 +457,47 @@ async fn git_push(path: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn git_commit(path: String, message: String) -> Result<(), String> {
-    git::commit(Path::new(&path), &message).await.map_err(|e| e.to_string())
+async fn git_commit(path: String, message: git::CommitMessage, options: Option<git::CommitOptions>) -> Result<(), String> {
+    let options = options.unwrap_or_default();
+    git::commit(Path::new(&path), &message, &options).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_commit_template(path: String) -> Result<Option<git::CommitMessage>, String> {
+    git::load_commit_template(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn lint_commit_message(message: git::CommitMessage, rules: Option<git::LintRules>) -> Result<Vec<git::LintIssue>, String> {
+    let rules = rules.unwrap_or_default();
+    Ok(git::lint_message(&message.format(), &rules))
 }
 
 #[tauri::command]
-async fn git_checkout(path: String, branch: String) -> Result<(), String> {
-    git::checkout(Path::new(&path), &branch).await.map_err(|e| e.to_string())
+async fn get_repository_authors(path: String) -> Result<Vec<git::AuthorEntry>, String> {
+    git::get_author_index(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn search_co_authors(path: String, query: String, limit: Option<usize>) -> Result<Vec<git::AuthorMatch>, String> {
+    git::search_authors(Path::new(&path), &query, limit.unwrap_or(20)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_is_head_pushed(path: String) -> Result<bool, String> {
+    git::is_head_pushed(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_reword_commit(path: String, hash: String, message: String) -> Result<(), String> {
+    git::reword_commit(Path::new(&path), &hash, &message).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_checkout(path: String, branch: String, mode: Option<git::CheckoutMode>) -> Result<git::CheckoutResult, String> {
+    git::checkout_with(Path::new(&path), &branch, mode.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -154,6 +535,55 @@ async fn set_global_git_config(name: String, email: String) -> Result<(), String
     git::set_global_config(&name, &email).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn list_git_config(path: String, scope: Option<git::ConfigScope>) -> Result<Vec<git::ConfigEntry>, String> {
+    git::config::list_config(Path::new(&path), scope).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_git_config(path: String, key: String) -> Result<Option<git::ConfigEntry>, String> {
+    git::config::get_config_entry(Path::new(&path), &key).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_git_config(path: String, key: String, value: String, scope: git::ConfigScope) -> Result<(), String> {
+    git::config::set_config(Path::new(&path), &key, &value, scope).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn unset_git_config(path: String, key: String, scope: git::ConfigScope) -> Result<(), String> {
+    git::config::unset_config(Path::new(&path), &key, scope).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_git_identity(path: String) -> Result<git::EffectiveIdentity, String> {
+    git::config::get_effective_identity(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_git_include_ifs(path: String) -> Result<Vec<git::IncludeIf>, String> {
+    git::config::list_include_ifs(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_include_if_identity(
+    directory: String,
+    include_file: String,
+    name: String,
+    email: String,
+    signing_key: Option<String>,
+) -> Result<(), String> {
+    git::config::set_include_if_identity(
+        Path::new(&directory),
+        Path::new(&include_file),
+        &name,
+        &email,
+        signing_key.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn git_stage_all(path: String) -> Result<(), String> {
     let mut cmd = std::process::Command::new("git");
@@ -226,10 +656,90 @@ async fn git_cherry_pick_commit(path: String, hash: String) -> Result<(), String
     git::cherry_pick_commit(Path::new(&path), &hash).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn git_create_tag(path: String, name: String, target: String, message: String, sign: Option<bool>) -> Result<(), String> {
+    git::create_tag(Path::new(&path), &name, &target, &message, sign).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_signing_config(path: String) -> Result<git::SigningConfig, String> {
+    Ok(git::get_signing_config(Path::new(&path)).await)
+}
+
+/// Verify the signatures of the given commits, keyed by hash
+#[tauri::command]
+async fn verify_commit_signatures(
+    path: String,
+    hashes: Vec<String>,
+) -> Result<std::collections::HashMap<String, git::CommitSignature>, String> {
+    git::verify_commit_signatures(Path::new(&path), &hashes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn git_delete_branch(path: String, branch: String, force: bool) -> Result<(), String> {
     git::delete_branch(Path::new(&path), &branch, force).await.map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+async fn git_create_branch_at(path: String, branch: String, start_point: String, track: Option<bool>) -> Result<(), String> {
+    git::create_branch_at(Path::new(&path), &branch, &start_point, track).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_rename_branch(path: String, old_name: String, new_name: String, rename_remote: bool) -> Result<(), String> {
+    git::rename_branch(Path::new(&path), &old_name, &new_name, rename_remote).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_set_branch_upstream(path: String, branch: String, upstream: Option<String>) -> Result<(), String> {
+    git::set_branch_upstream(Path::new(&path), &branch, upstream.as_deref()).await.map_err(|e| e.to_string())
+}
+
+/// Check a branch name for validity and existing branches
+#[tauri::command]
+async fn validate_branch_name(path: String, name: String) -> Result<git::BranchNameCheck, String> {
+    git::validate_branch_name(Path::new(&path), &name).await.map_err(|e| e.to_string())
+}
+
+/// Generate an unused branch name from a template, ticket and description
+#[tauri::command]
+async fn suggest_branch_name(path: String, template: String, input: git::BranchNameInput) -> Result<String, String> {
+    git::suggest_branch_name(Path::new(&path), &template, &input).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_workspace_branch_templates(workspace_id: String, templates: Vec<String>) -> Result<(), String> {
+    workspace::set_workspace_branch_templates(&workspace_id, templates).await
+}
+
+/// Find local branches that are gone upstream, merged or inactive
+#[tauri::command]
+async fn find_stale_branches(path: String, inactive_days: Option<u32>) -> Result<Vec<git::StaleBranch>, String> {
+    git::find_stale_branches(Path::new(&path), inactive_days).await.map_err(|e| e.to_string())
+}
+
+/// Delete branches, keeping their tips restorable
+#[tauri::command]
+async fn git_delete_branches(path: String, branches: Vec<String>) -> Result<Vec<git::DeletedBranch>, String> {
+    Ok(git::delete_branches(Path::new(&path), &branches).await)
+}
+
+#[tauri::command]
+async fn list_deleted_branches(path: String) -> Result<Vec<git::DeletedBranchRecord>, String> {
+    git::list_deleted_branches(Path::new(&path)).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_restore_branch(path: String, branch: String, tip: Option<String>) -> Result<(), String> {
+    git::restore_branch(Path::new(&path), &branch, tip.as_deref()).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn git_delete_remote_branch(path: String, remote: String, branch: String) -> Result<(), String> {
+    git::delete_remote_branch(Path::new(&path), &remote, &branch).await.map_err(|e| e.to_string())
+}
 #[tauri::command]
 async fn git_init(path: String) -> Result<(), String> {
     git::init(Path::new(&path)).await.map_err(|e| e.to_string())
@@ -362,10 +872,8 @@ async fn create_github_repository(token: String, name: String, description: Opti
 
 #[tauri::command]
 async fn get_github_avatars(remote_url: String, commit_hashes: Vec<String>) -> Result<std::collections::HashMap<String, String>, String> {
-    // Run blocking requests in a separate thread
-    tauri::async_runtime::spawn_blocking(move || {
-        Ok(github::get_commit_avatars(&remote_url, commit_hashes))
-    }).await.map_err(|e| e.to_string())?
+    // Cached avatars are served from disk; the rest are fetched on a blocking thread
+    cache::get_cached_avatars(&remote_url, commit_hashes).await
 }
 
 #[tauri::command]
@@ -505,21 +1013,62 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_window_state::Builder::default().build())
+        .setup(|app| {
+            tauri::async_runtime::spawn(workspace::run_discovery_scheduler(app.handle().clone()));
+            tauri::async_runtime::spawn(workspace::run_auto_fetch_scheduler(app.handle().clone()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             scan_repositories,
+            scan_repositories_stream,
             get_repository_status,
             list_branches,
             get_repository_info,
+            get_repository_summaries,
             get_workspaces,
             create_workspace,
             delete_workspace,
             add_repository_to_workspace,
+            rename_workspace,
+            set_workspace_color,
+            remove_repository_from_workspace,
+            reorder_workspace_repositories,
+            move_repository_between_workspaces,
+            set_repository_alias,
+            set_repository_pinned,
+            export_workspace,
+            plan_workspace_import,
+            import_workspace,
+            set_workspace_discovery,
+            rescan_workspace,
+            set_workspace_identity,
+            check_workspace_identity,
+            apply_workspace_identity,
+            run_workspace_batch,
+            set_workspace_auto_fetch,
+            set_repository_auto_fetch,
+            set_auto_fetch_open_repositories,
+            set_network_online,
+            run_repositories_batch,
             git_fetch,
             git_pull,
             git_push,
             git_commit,
+            git_is_head_pushed,
+            get_commit_template,
+            lint_commit_message,
+            get_repository_authors,
+            search_co_authors,
+            git_reword_commit,
             get_global_git_config,
             set_global_git_config,
+            list_git_config,
+            get_git_config,
+            set_git_config,
+            unset_git_config,
+            get_git_identity,
+            list_git_include_ifs,
+            set_include_if_identity,
             git_stage_file,
             git_unstage_file,
             git_stage_all,
@@ -531,15 +1080,43 @@ pub fn run() {
             git_revert_commit,
             git_reset_to_commit,
             git_cherry_pick_commit,
+            git_create_tag,
+            get_signing_config,
+            verify_commit_signatures,
             git_delete_branch,
+            git_create_branch_at,
+            git_rename_branch,
+            git_set_branch_upstream,
+            git_delete_remote_branch,
+            find_stale_branches,
+            validate_branch_name,
+            suggest_branch_name,
+            set_workspace_branch_templates,
+            git_delete_branches,
+            list_deleted_branches,
+            git_restore_branch,
             git_undo_commit,
             git_resolve_conflict,
             get_file_diff,
             git_discard_changes,
             git_add_to_gitignore,
             get_git_history,
+            index_repository_history,
+            clear_repository_cache,
+            search_commits,
+            search_commits_stream,
+            cancel_commit_search,
             get_commit_files,
             get_commit_file_diff,
+            get_file_history,
+            blame_file,
+            blame_file_stream,
+            cancel_blame,
+            get_file_diff_in_commit,
+            get_file_at_revision,
+            compare_refs,
+            get_compare_file_diff,
+            preview_merge,
             create_github_repository,
             get_github_avatars,
             git_init,
@@ -0,0 +1,44 @@
+//! Glob matching
+//!
+//! Minimal `*`, `**` and `?` matching for slash-separated relative paths.
+
+/// Check whether `path` matches `pattern`
+///
+/// `*` and `?` never cross a `/`, while `**` matches any number of path segments.
+/// Backslashes are treated as separators so Windows paths behave the same.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.replace('\\', "/");
+    let path = path.replace('\\', "/");
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match_segments(&pattern, &path)
+}
+
+/// Check whether `path` matches any of `patterns`
+pub fn glob_match_any(patterns: &[String], path: &str) -> bool {
+    patterns.iter().any(|p| glob_match(p, path))
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                let segment: Vec<char> = segment.chars().collect();
+                let name: Vec<char> = name.chars().collect();
+                match_segment(&segment, &name) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| match_segment(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
@@ -2,8 +2,11 @@
 //!
 //! Handles repository discovery and metadata caching.
 
+pub mod glob;
 pub mod scanner;
+pub mod summary;
 pub mod types;
 
-pub use scanner::{scan_repositories, get_repository_info};
+pub use scanner::{scan_repositories, scan_repositories_with, get_repository_info, ScanOptions};
+pub use summary::{summarize_repositories, summarize_repository};
 pub use types::*;
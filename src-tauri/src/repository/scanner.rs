@@ -2,17 +2,25 @@
 //!
 //! Scans directories to discover Git repositories.
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
 use tokio::fs;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 use crate::git;
+use crate::git::RepositoryKind;
 
+use super::glob::glob_match_any;
 use super::types::Repository;
 
-/// Maximum depth to scan for repositories
+/// Default maximum depth to scan for repositories
 const MAX_SCAN_DEPTH: u32 = 4;
 
-/// Directories to skip during scanning
+/// Directories skipped during scanning unless configured otherwise
 const SKIP_DIRS: &[&str] = &[
     "node_modules",
     ".git",
@@ -24,64 +32,98 @@ const SKIP_DIRS: &[&str] = &[
     "__pycache__",
 ];
 
-/// Scan a directory for Git repositories
-pub async fn scan_repositories(path: &Path) -> Result<Vec<Repository>, String> {
-    let mut repos = Vec::new();
-    let mut stack: Vec<(PathBuf, u32)> = vec![(path.to_path_buf(), 0)];
+/// Number of directories read at the same time
+const SCAN_CONCURRENCY: usize = 16;
 
-    while let Some((current_path, depth)) = stack.pop() {
-        if depth > MAX_SCAN_DEPTH {
-            continue;
-        }
+/// Options controlling a repository scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    pub max_depth: u32,
+    /// Globs matched against directory names and paths relative to the scan root
+    pub skip_patterns: Vec<String>,
+    /// Skip directories starting with a dot
+    pub skip_hidden: bool,
+    pub follow_symlinks: bool,
+    /// Do not look for nested repositories inside a repository that was found
+    pub stop_at_repositories: bool,
+}
 
-        // Skip if not a directory
-        if !current_path.is_dir() {
-            continue;
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: MAX_SCAN_DEPTH,
+            skip_patterns: SKIP_DIRS.iter().map(|s| s.to_string()).collect(),
+            skip_hidden: true,
+            follow_symlinks: false,
+            stop_at_repositories: false,
         }
+    }
+}
 
-        // Check if this is a Git repository
-        if git::is_git_repo(&current_path).await {
-            let name = current_path
-                .file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-
-            let remote_url = get_remote_url(&current_path).await.ok();
+/// Scan a directory for Git repositories
+pub async fn scan_repositories(path: &Path) -> Result<Vec<Repository>, String> {
+    scan_repositories_with(path, &ScanOptions::default(), |_| {}).await
+}
 
-            repos.push(Repository {
-                path: current_path.to_string_lossy().to_string(),
-                name,
-                remote_url,
-                last_commit: None,
-            });
+/// Scan a directory for Git repositories, calling `on_found` as each one is discovered
+///
+/// Directories of the same depth are read in parallel. The returned list is sorted
+/// by name; `on_found` sees repositories in discovery order.
+pub async fn scan_repositories_with<F>(
+    path: &Path,
+    options: &ScanOptions,
+    mut on_found: F,
+) -> Result<Vec<Repository>, String>
+where
+    F: FnMut(&Repository),
+{
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
 
-            // We used to stop here, but now we continue scanning to find nested repos
-            // if depth > 0 {
-            //    continue;
-            // }
-        }
+    let root = Arc::new(path.to_path_buf());
+    let options = Arc::new(options.clone());
+    let semaphore = Arc::new(Semaphore::new(SCAN_CONCURRENCY));
 
-        // Read directory entries
-        let mut entries = match fs::read_dir(&current_path).await {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+    let mut repos = Vec::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    let mut frontier: Vec<PathBuf> = vec![path.to_path_buf()];
+    let mut depth = 0;
 
-        while let Ok(Some(entry)) = entries.next_entry().await {
-            let entry_path = entry.path();
+    while !frontier.is_empty() && depth <= options.max_depth {
+        let mut tasks = JoinSet::new();
 
-            // Skip hidden directories and known non-repo directories
-            if let Some(name) = entry_path.file_name() {
-                let name_str = name.to_string_lossy();
-                if name_str.starts_with('.') || SKIP_DIRS.contains(&name_str.as_ref()) {
+        for dir in frontier.drain(..) {
+            // Symlinked directories can lead back to a directory we already scanned
+            if options.follow_symlinks {
+                let canonical = fs::canonicalize(&dir).await.unwrap_or_else(|_| dir.clone());
+                if !visited.insert(canonical) {
                     continue;
                 }
             }
 
-            if entry_path.is_dir() {
-                stack.push((entry_path, depth + 1));
+            let root = Arc::clone(&root);
+            let options = Arc::clone(&options);
+            let semaphore = Arc::clone(&semaphore);
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                scan_directory(&root, dir, &options).await
+            });
+        }
+
+        let mut next = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let Ok((repo, children)) = joined else { continue };
+            if let Some(repo) = repo {
+                on_found(&repo);
+                repos.push(repo);
             }
+            next.extend(children);
         }
+
+        frontier = next;
+        depth += 1;
     }
 
     // Sort by name
@@ -90,32 +132,131 @@ pub async fn scan_repositories(path: &Path) -> Result<Vec<Repository>, String> {
     Ok(repos)
 }
 
-/// Get the remote URL for a repository
-async fn get_remote_url(repo_path: &Path) -> Result<String, String> {
-    use crate::git::executor::execute_string;
+/// Inspect one directory: is it a repository, and which subdirectories to visit next
+async fn scan_directory(root: &Path, dir: PathBuf, options: &ScanOptions) -> (Option<Repository>, Vec<PathBuf>) {
+    let mut repo = None;
 
-    execute_string(repo_path, &["remote", "get-url", "origin"])
-        .await
-        .map_err(|e| e.to_string())
-}
+    // Check if this is a Git repository
+    if let Some(kind) = git::detect_repository_kind(&dir).await {
+        repo = Some(build_repository(&dir, kind).await);
+        // The inside of a bare repository is git's own storage
+        if options.stop_at_repositories || kind == RepositoryKind::Bare {
+            return (repo, Vec::new());
+        }
+    }
 
-/// Get metadata for a single repository
-pub async fn get_repository_info(path: &Path) -> Result<Repository, String> {
-    if !git::is_git_repo(path).await {
-        return Err("Not a Git repository".to_string());
+    // Read directory entries
+    let mut entries = match fs::read_dir(&dir).await {
+        Ok(e) => e,
+        Err(_) => return (repo, Vec::new()),
+    };
+
+    let mut children = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let entry_path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        // Skip hidden directories and configured non-repo directories
+        if options.skip_hidden && name.starts_with('.') {
+            continue;
+        }
+        let relative = entry_path
+            .strip_prefix(root)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| name.clone());
+        if glob_match_any(&options.skip_patterns, &name) || glob_match_any(&options.skip_patterns, &relative) {
+            continue;
+        }
+
+        let is_dir = match entry.file_type().await {
+            Ok(t) if t.is_dir() => true,
+            Ok(t) if t.is_symlink() && options.follow_symlinks => fs::metadata(&entry_path)
+                .await
+                .map(|m| m.is_dir())
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        if is_dir {
+            children.push(entry_path);
+        }
     }
 
+    (repo, children)
+}
+
+/// Build the repository entry for a discovered path
+async fn build_repository(path: &Path, kind: RepositoryKind) -> Repository {
     let name = path
         .file_name()
         .map(|s| s.to_string_lossy().to_string())
         .unwrap_or_else(|| "unknown".to_string());
 
-    let remote_url = get_remote_url(path).await.ok();
-
-    Ok(Repository {
+    Repository {
         path: path.to_string_lossy().to_string(),
         name,
-        remote_url,
+        remote_url: get_remote_url(path, kind).await.ok(),
         last_commit: None,
-    })
+        kind,
+        summary: None,
+    }
+}
+
+/// Read the origin URL straight from `.git/config`, avoiding a git process
+///
+/// Returns `None` when the config cannot be read directly or uses URL rewriting,
+/// in which case git itself has to resolve the URL.
+async fn read_origin_url(repo_path: &Path, kind: RepositoryKind) -> Option<Option<String>> {
+    // Worktrees and submodules have a `.git` file; leave those to git
+    let config_path = match kind {
+        RepositoryKind::Normal => repo_path.join(".git").join("config"),
+        RepositoryKind::Bare => repo_path.join("config"),
+        _ => return None,
+    };
+    let config = fs::read_to_string(config_path).await.ok()?;
+    if config.contains("insteadOf") || config.contains("pushInsteadOf") || config.contains("[include") {
+        return None;
+    }
+
+    let mut in_origin = false;
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+            continue;
+        }
+        if in_origin {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim().eq_ignore_ascii_case("url") {
+                    return Some(Some(value.trim().trim_matches('"').to_string()));
+                }
+            }
+        }
+    }
+
+    Some(None)
+}
+
+/// Get the remote URL for a repository
+async fn get_remote_url(repo_path: &Path, kind: RepositoryKind) -> Result<String, String> {
+    use crate::git::executor::execute_string;
+
+    match read_origin_url(repo_path, kind).await {
+        Some(Some(url)) => return Ok(url),
+        Some(None) => return Err("No origin remote".to_string()),
+        None => {}
+    }
+
+    execute_string(repo_path, &["remote", "get-url", "origin"])
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get metadata for a single repository
+pub async fn get_repository_info(path: &Path) -> Result<Repository, String> {
+    let kind = git::detect_repository_kind(path)
+        .await
+        .ok_or("Not a Git repository")?;
+
+    Ok(build_repository(path, kind).await)
 }
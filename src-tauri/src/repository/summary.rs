@@ -0,0 +1,185 @@
+//! Repository summaries
+//!
+//! Lazily computed, cached metadata for the repository list.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::git;
+use crate::git::executor::{execute_string, git_dir};
+use crate::git::{CommitInfo, RepositoryKind};
+
+use super::types::{Repository, RepositorySummary};
+
+/// How long a cached summary is trusted when nothing in the git directory changed
+const SUMMARY_TTL: Duration = Duration::from_secs(30);
+
+/// Number of repositories summarized at the same time
+const SUMMARY_CONCURRENCY: usize = 8;
+
+struct CachedSummary {
+    fingerprint: Vec<Option<SystemTime>>,
+    computed_at: Instant,
+    last_commit: Option<CommitInfo>,
+    summary: RepositorySummary,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedSummary>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedSummary>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Modification times of the files that change on commit, checkout, staging and fetch
+fn fingerprint(git_dir: &Path) -> Vec<Option<SystemTime>> {
+    ["HEAD", "index", "FETCH_HEAD", "ORIG_HEAD", "packed-refs", "logs/HEAD"]
+        .iter()
+        .map(|f| modified(&git_dir.join(f)))
+        .collect()
+}
+
+/// Get the most recent commit on HEAD and its committer time
+async fn get_last_commit(repo_path: &Path) -> Option<(CommitInfo, Option<i64>)> {
+    let output = execute_string(
+        repo_path,
+        &["log", "-1", "--date=iso-strict", "--format=%H%x09%h%x09%an%x09%ae%x09%ad%x09%ct%x09%s"],
+    )
+    .await
+    .ok()?;
+
+    let parts: Vec<&str> = output.splitn(7, '\t').collect();
+    if parts.len() < 7 {
+        return None;
+    }
+
+    let commit = CommitInfo {
+        hash: parts[0].to_string(),
+        short_hash: parts[1].to_string(),
+        author: parts[2].to_string(),
+        email: parts[3].to_string(),
+        timestamp: parts[4].to_string(),
+        message: parts[6].to_string(),
+        signature: Default::default(),
+        signer: None,
+    };
+    Some((commit, parts[5].parse().ok()))
+}
+
+/// Compute the summary of a repository from scratch
+async fn compute_summary(repo_path: &Path, kind: RepositoryKind, git_dir: &Path) -> (Option<CommitInfo>, RepositorySummary) {
+    let (last_commit, last_activity) = match get_last_commit(repo_path).await {
+        Some((commit, time)) => (Some(commit), time),
+        None => (None, None),
+    };
+
+    let branch = execute_string(repo_path, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .await
+        .ok()
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+
+    let is_dirty = match kind {
+        RepositoryKind::Bare => false,
+        _ => execute_string(repo_path, &["status", "--porcelain=v1"])
+            .await
+            .map(|s| !s.trim().is_empty())
+            .unwrap_or(false),
+    };
+
+    let (ahead, behind) = git::get_tracking_info(repo_path).await.unwrap_or((0, 0));
+    let default_branch = git::get_default_branch(repo_path).await.ok().flatten();
+
+    let last_fetch = modified(&git_dir.join("FETCH_HEAD"))
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    let summary = RepositorySummary {
+        branch,
+        is_dirty,
+        ahead,
+        behind,
+        default_branch,
+        last_fetch,
+        last_activity,
+    };
+
+    (last_commit, summary)
+}
+
+/// Fill in `last_commit` and `summary` of a repository, using the cache when possible
+pub async fn summarize_repository(mut repo: Repository, force: bool) -> Repository {
+    let path = PathBuf::from(&repo.path);
+    let Ok(git_dir) = git_dir(&path).await else {
+        return repo;
+    };
+    let fingerprint = fingerprint(&git_dir);
+
+    if !force {
+        if let Ok(cache) = cache().lock() {
+            if let Some(cached) = cache.get(&path) {
+                if cached.fingerprint == fingerprint && cached.computed_at.elapsed() < SUMMARY_TTL {
+                    repo.last_commit = cached.last_commit.clone();
+                    repo.summary = Some(cached.summary.clone());
+                    return repo;
+                }
+            }
+        }
+    }
+
+    let (last_commit, summary) = compute_summary(&path, repo.kind, &git_dir).await;
+
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(
+            path,
+            CachedSummary {
+                fingerprint,
+                computed_at: Instant::now(),
+                last_commit: last_commit.clone(),
+                summary: summary.clone(),
+            },
+        );
+    }
+
+    repo.last_commit = last_commit;
+    repo.summary = Some(summary);
+    repo
+}
+
+/// Summarize several repositories with bounded concurrency
+///
+/// Paths that are no longer repositories are left out. The result is sorted by
+/// most recent commit first.
+pub async fn summarize_repositories(paths: Vec<String>, force: bool) -> Vec<Repository> {
+    let semaphore = Arc::new(Semaphore::new(SUMMARY_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+
+    for path in paths {
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            let repo = super::get_repository_info(Path::new(&path)).await.ok()?;
+            Some(summarize_repository(repo, force).await)
+        });
+    }
+
+    let mut repos = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(Some(repo)) = joined {
+            repos.push(repo);
+        }
+    }
+
+    repos.sort_by(|a, b| {
+        let activity = |r: &Repository| r.summary.as_ref().and_then(|s| s.last_activity);
+        activity(b).cmp(&activity(a))
+    });
+    repos
+}
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::git::CommitInfo;
+use crate::git::{CommitInfo, RepositoryKind};
 
 /// A Git repository
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +11,26 @@ pub struct Repository {
     pub name: String,
     pub remote_url: Option<String>,
     pub last_commit: Option<CommitInfo>,
+    #[serde(default)]
+    pub kind: RepositoryKind,
+    /// Filled in lazily by `summarize_repositories`
+    #[serde(default)]
+    pub summary: Option<RepositorySummary>,
+}
+
+/// Status overview shown in the repository list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySummary {
+    /// `None` when HEAD is detached
+    pub branch: Option<String>,
+    pub is_dirty: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub default_branch: Option<String>,
+    /// Unix time of the last fetch, from `FETCH_HEAD`
+    pub last_fetch: Option<u64>,
+    /// Unix committer time of the last commit, used to sort by recent activity
+    pub last_activity: Option<i64>,
 }
 
 
@@ -0,0 +1,318 @@
+//! Background auto-fetch
+//!
+//! Periodically fetches open repositories and workspaces that opted in, backing off
+//! after failures and pausing while offline or in power saving mode.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::git;
+
+use super::persistence::{load_workspaces, update_workspace};
+use super::types::AutoFetchConfig;
+
+/// How often the scheduler looks for repositories that are due
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+
+/// Interval for open repositories that are not covered by a workspace setting
+const DEFAULT_INTERVAL_MINUTES: u32 = 5;
+
+/// Upper bound for the delay after repeated failures
+const MAX_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Number of repositories fetched at the same time
+const FETCH_CONCURRENCY: usize = 4;
+
+/// Event emitted when a fetch brought in new commits or started failing
+pub const AUTO_FETCH_EVENT: &str = "auto-fetch";
+
+/// Outcome of a background fetch, sent to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoFetchEvent {
+    pub path: String,
+    pub ahead: u32,
+    pub behind: u32,
+    /// Commits fetched for the current branch's upstream since the previous fetch
+    pub new_commits: u32,
+    pub error: Option<String>,
+}
+
+/// Why a fetch failed, which decides how long to wait before the next attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    Network,
+    /// Retrying cannot help until the user fixes their credentials
+    Auth,
+    Other,
+}
+
+#[derive(Debug, Default)]
+struct RepoSchedule {
+    next_attempt: Option<Instant>,
+    failures: u32,
+}
+
+#[derive(Debug)]
+struct AutoFetchState {
+    open: HashSet<String>,
+    online: bool,
+    schedules: HashMap<String, RepoSchedule>,
+}
+
+fn state() -> &'static Mutex<AutoFetchState> {
+    static STATE: OnceLock<Mutex<AutoFetchState>> = OnceLock::new();
+    STATE.get_or_init(|| {
+        Mutex::new(AutoFetchState {
+            open: HashSet::new(),
+            online: true,
+            schedules: HashMap::new(),
+        })
+    })
+}
+
+/// Replace the set of repositories currently open in the UI
+pub fn set_open_repositories(paths: Vec<String>) {
+    if let Ok(mut state) = state().lock() {
+        state.open = paths.into_iter().collect();
+    }
+}
+
+/// Record whether the frontend considers the machine online
+pub fn set_network_online(online: bool) {
+    if let Ok(mut state) = state().lock() {
+        state.online = online;
+    }
+}
+
+/// Set or clear the auto-fetch settings of a workspace
+pub async fn set_workspace_auto_fetch(workspace_id: &str, config: Option<AutoFetchConfig>) -> Result<(), String> {
+    if config.as_ref().is_some_and(|c| c.interval_minutes == 0) {
+        return Err("Auto-fetch interval must be at least one minute".to_string());
+    }
+
+    update_workspace(workspace_id, |workspace| {
+        workspace.auto_fetch = config;
+        Ok(())
+    })
+    .await
+}
+
+/// Opt a single repository of a workspace in or out of auto-fetch
+pub async fn set_repository_auto_fetch(workspace_id: &str, repo_path: &str, enabled: bool) -> Result<(), String> {
+    update_workspace(workspace_id, |workspace| {
+        if !workspace.repositories.iter().any(|p| p == repo_path) {
+            return Err("Repository not found in workspace".to_string());
+        }
+        let config = workspace.auto_fetch.get_or_insert_with(AutoFetchConfig::default);
+        config.excluded.retain(|p| p != repo_path);
+        if !enabled {
+            config.excluded.push(repo_path.to_string());
+        }
+        Ok(())
+    })
+    .await
+}
+
+fn classify_failure(message: &str) -> FailureKind {
+    let lower = message.to_lowercase();
+    if lower.contains("authentication failed")
+        || lower.contains("permission denied")
+        || lower.contains("could not read username")
+        || lower.contains("could not read password")
+        || lower.contains("host key verification failed")
+        || lower.contains("403")
+    {
+        FailureKind::Auth
+    } else if lower.contains("could not resolve host")
+        || lower.contains("could not resolve hostname")
+        || lower.contains("connection timed out")
+        || lower.contains("connection refused")
+        || lower.contains("network is unreachable")
+        || lower.contains("unable to access")
+    {
+        FailureKind::Network
+    } else {
+        FailureKind::Other
+    }
+}
+
+/// Delay before retrying after `failures` consecutive failures
+fn backoff(interval: Duration, failures: u32, kind: FailureKind) -> Duration {
+    if kind == FailureKind::Auth {
+        return MAX_BACKOFF;
+    }
+    let factor = 2u32.saturating_pow(failures.min(16));
+    interval.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+/// Whether the operating system reports a power saving mode
+async fn power_saver_active() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(profile) = tokio::fs::read_to_string("/sys/firmware/acpi/platform_profile").await {
+            if profile.trim() == "low-power" {
+                return true;
+            }
+        }
+        if let Ok(output) = tokio::process::Command::new("powerprofilesctl").arg("get").output().await {
+            return String::from_utf8_lossy(&output.stdout).trim() == "power-saver";
+        }
+        false
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match tokio::process::Command::new("pmset").arg("-g").output().await {
+            Ok(output) => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .any(|l| l.split_whitespace().collect::<Vec<_>>() == ["lowpowermode", "1"]),
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        false
+    }
+}
+
+/// Repositories to auto-fetch and their interval
+///
+/// Open repositories use the default interval unless a workspace sets a shorter one.
+/// A repository excluded by any workspace is never fetched.
+async fn collect_targets() -> HashMap<String, Duration> {
+    let mut intervals: HashMap<String, u32> = HashMap::new();
+    let mut excluded: HashSet<String> = HashSet::new();
+
+    if let Ok(open) = state().lock() {
+        for path in &open.open {
+            intervals.insert(path.clone(), DEFAULT_INTERVAL_MINUTES);
+        }
+    }
+
+    if let Ok(workspaces) = load_workspaces().await {
+        for workspace in workspaces {
+            let Some(config) = workspace.auto_fetch else { continue };
+            excluded.extend(config.excluded.iter().cloned());
+            if !config.enabled {
+                continue;
+            }
+            for path in workspace.repositories {
+                let interval = intervals.entry(path).or_insert(config.interval_minutes);
+                *interval = (*interval).min(config.interval_minutes);
+            }
+        }
+    }
+
+    intervals
+        .into_iter()
+        .filter(|(path, _)| !excluded.contains(path))
+        .map(|(path, minutes)| (path, Duration::from_secs(u64::from(minutes.max(1)) * 60)))
+        .collect()
+}
+
+/// Fetch one repository and report how far behind its upstream it now is
+async fn fetch_repository(path: &str) -> Result<(u32, u32, u32), String> {
+    let repo = Path::new(path);
+    let (_, behind_before) = git::get_tracking_info(repo).await.unwrap_or((0, 0));
+
+    git::fetch(repo).await.map_err(|e| e.message)?;
+
+    // Branches without an upstream have nothing to be behind
+    let (ahead, behind) = git::get_tracking_info(repo).await.unwrap_or((0, 0));
+    Ok((ahead, behind, behind.saturating_sub(behind_before)))
+}
+
+/// Fetch the due repositories and update their schedules
+async fn run_due_fetches(app: &AppHandle, targets: HashMap<String, Duration>) {
+    let now = Instant::now();
+    let due: Vec<(String, Duration)> = match state().lock() {
+        Ok(mut state) => {
+            state.schedules.retain(|path, _| targets.contains_key(path));
+            targets
+                .into_iter()
+                .filter(|(path, _)| {
+                    let schedule = state.schedules.entry(path.clone()).or_default();
+                    schedule.next_attempt.is_none_or(|next| next <= now)
+                })
+                .collect()
+        }
+        Err(_) => return,
+    };
+
+    let semaphore = Arc::new(Semaphore::new(FETCH_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (path, interval) in due {
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            let result = fetch_repository(&path).await;
+            (path, interval, result)
+        });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((path, interval, result)) = joined else { continue };
+
+        let event = {
+            let Ok(mut state) = state().lock() else { return };
+            let schedule = state.schedules.entry(path.clone()).or_default();
+
+            match result {
+                Ok((ahead, behind, new_commits)) => {
+                    schedule.failures = 0;
+                    schedule.next_attempt = Some(Instant::now() + interval);
+                    (new_commits > 0).then_some(AutoFetchEvent {
+                        path,
+                        ahead,
+                        behind,
+                        new_commits,
+                        error: None,
+                    })
+                }
+                Err(message) => {
+                    let kind = classify_failure(&message);
+                    let first_failure = schedule.failures == 0;
+                    schedule.failures += 1;
+                    schedule.next_attempt = Some(Instant::now() + backoff(interval, schedule.failures, kind));
+                    // Only the start of a failure streak is worth telling the user about
+                    first_failure.then_some(AutoFetchEvent {
+                        path,
+                        ahead: 0,
+                        behind: 0,
+                        new_commits: 0,
+                        error: Some(message),
+                    })
+                }
+            }
+        };
+
+        if let Some(event) = event {
+            let _ = app.emit(AUTO_FETCH_EVENT, event);
+        }
+    }
+}
+
+/// Fetch repositories in the background for the lifetime of the app
+pub async fn run_auto_fetch_scheduler(app: AppHandle) {
+    loop {
+        tokio::time::sleep(SCHEDULER_TICK).await;
+
+        let online = state().lock().map(|s| s.online).unwrap_or(true);
+        if !online || power_saver_active().await {
+            continue;
+        }
+
+        let targets = collect_targets().await;
+        if !targets.is_empty() {
+            run_due_fetches(&app, targets).await;
+        }
+    }
+}
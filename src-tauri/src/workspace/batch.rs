@@ -0,0 +1,316 @@
+//! Batch operations
+//!
+//! Runs git operations across many repositories with bounded concurrency.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::git;
+use crate::git::executor::execute;
+
+use super::persistence::load_workspaces;
+
+/// Default number of repositories processed at the same time
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Read-only git subcommands that may be run across a workspace
+const READ_COMMANDS: &[&str] = &[
+    "status", "log", "describe", "shortlog", "rev-parse", "show-ref", "for-each-ref", "branch", "tag",
+    "remote",
+];
+
+/// Subcommands that update the repository, refused on read-only (bare) repositories
+const WRITE_COMMANDS: &[&str] = &["fetch", "pull", "gc"];
+
+/// Options of the listing modes of `branch` and `tag`; anything else could create,
+/// move or delete refs
+const LISTING_OPTIONS: &[&str] = &[
+    "-l", "--list", "-a", "--all", "-r", "--remotes", "-v", "-vv", "--verbose", "--merged", "--no-merged",
+    "--contains", "--no-contains", "--points-at", "--sort", "--format", "--color", "--no-color", "--column",
+    "--no-column", "-i", "--ignore-case", "--abbrev", "--no-abbrev", "--show-current",
+];
+
+/// `git remote` modes that only read
+const REMOTE_LISTING_MODES: &[&str] = &["show", "get-url"];
+
+/// Options of `git remote`, `remote show` and `remote get-url`
+const REMOTE_LISTING_OPTIONS: &[&str] = &["-v", "--verbose", "-n", "--push", "--all"];
+
+/// Options that can run arbitrary programs or write outside the repository
+const DENIED_OPTIONS: &[&str] = &[
+    "--upload-pack", "--receive-pack", "--exec", "--output", "--ext-diff", "--config", "--git-dir",
+    "--work-tree", "--exec-path",
+];
+
+/// An operation to run on every repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Fetch,
+    /// Fast-forward only, so diverged repositories are reported instead of merged
+    Pull,
+    Status,
+    /// An allow-listed git command, e.g. `["gc", "--auto"]`
+    Command { args: Vec<String> },
+}
+
+/// Condensed working tree status of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchStatus {
+    pub branch: String,
+    pub is_dirty: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicts: usize,
+}
+
+/// Result of a batch operation for one repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub path: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Status after the operation, when it could be read
+    pub status: Option<BatchStatus>,
+    /// Output of a `Command` operation
+    pub output: Option<String>,
+}
+
+/// Reject commands outside the allow-list and options that could escape it
+fn validate_command(args: &[String]) -> Result<(), String> {
+    let subcommand = args.first().ok_or("No git command given")?;
+    if !READ_COMMANDS.contains(&subcommand.as_str()) && !WRITE_COMMANDS.contains(&subcommand.as_str()) {
+        return Err(format!("git {} is not allowed in batch operations", subcommand));
+    }
+    for arg in args {
+        if is_denied_option(arg) {
+            return Err(format!("Option {} is not allowed in batch operations", arg));
+        }
+    }
+
+    let rest = &args[1..];
+    match subcommand.as_str() {
+        "branch" | "tag" => validate_listing(subcommand, rest),
+        "remote" => validate_remote_listing(rest),
+        _ => Ok(()),
+    }
+}
+
+/// Option name without its `=value`
+fn option_name(arg: &str) -> &str {
+    arg.split('=').next().unwrap_or(arg)
+}
+
+/// Only allow `branch` and `tag` to list; patterns require `--list`, since
+/// `git branch <name>` creates a branch
+fn validate_listing(subcommand: &str, args: &[String]) -> Result<(), String> {
+    let listing = args.iter().any(|a| a == "-l" || a == "--list");
+    for arg in args {
+        if arg.starts_with('-') {
+            if !LISTING_OPTIONS.contains(&option_name(arg)) {
+                return Err(format!("git {} {} is not allowed in batch operations; only listing is", subcommand, arg));
+            }
+        } else if !listing {
+            return Err(format!("git {} {} would create a ref; use --list to filter by pattern", subcommand, arg));
+        }
+    }
+    Ok(())
+}
+
+/// Only allow `git remote`, `remote show` and `remote get-url`
+fn validate_remote_listing(args: &[String]) -> Result<(), String> {
+    let mut positional = args.iter().filter(|a| !a.starts_with('-'));
+    if let Some(mode) = positional.next() {
+        if !REMOTE_LISTING_MODES.contains(&mode.as_str()) {
+            return Err(format!("git remote {} is not allowed in batch operations; only listing is", mode));
+        }
+    }
+    match args.iter().find(|a| a.starts_with('-') && !REMOTE_LISTING_OPTIONS.contains(&option_name(a))) {
+        Some(arg) => Err(format!("Option {} is not allowed in batch operations", arg)),
+        None => Ok(()),
+    }
+}
+
+/// Whether `arg` is a denied long option, including the unique prefixes git accepts
+/// for it (`--upload=…` runs as `--upload-pack=…`)
+fn is_denied_option(arg: &str) -> bool {
+    let Some(name) = arg.strip_prefix("--") else {
+        return false;
+    };
+    let name = name.split('=').next().unwrap_or(name);
+    // A bare `--` ends the options
+    if name.is_empty() {
+        return false;
+    }
+
+    DENIED_OPTIONS.iter().any(|denied| {
+        let denied = denied.trim_start_matches('-');
+        denied.starts_with(name) || name.starts_with(denied)
+    })
+}
+
+async fn read_status(path: &Path) -> Option<BatchStatus> {
+    let status = git::get_status(path).await.ok()?;
+    Some(BatchStatus {
+        branch: status.branch,
+        is_dirty: !status.is_clean,
+        ahead: status.ahead,
+        behind: status.behind,
+        staged: status.staged.len(),
+        unstaged: status.unstaged.len(),
+        untracked: status.untracked.len(),
+        conflicts: status.conflicts.len(),
+    })
+}
+
+/// Run the operation on one repository
+async fn run_one(path: String, operation: &BatchOperation) -> BatchResult {
+    let repo = Path::new(&path);
+    let mut output = None;
+
+    let result = match operation {
+        BatchOperation::Fetch => git::fetch(repo).await.map_err(|e| e.to_string()),
+        BatchOperation::Pull => git::pull_ff_only(repo).await.map_err(|e| e.to_string()),
+        BatchOperation::Status => Ok(()),
+        BatchOperation::Command { args } => {
+            let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            // Read-only commands also work on bare repositories
+            let writable = match args.first() {
+                Some(subcommand) if WRITE_COMMANDS.contains(subcommand) => {
+                    git::ensure_writable(repo, &format!("batch {}", subcommand)).await
+                }
+                _ => Ok(()),
+            };
+            let ran = match writable {
+                Ok(()) => execute(repo, &args).await,
+                Err(e) => Err(e),
+            };
+            match ran {
+                Ok(out) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout).trim_end().to_string();
+                    output = Some(stdout);
+                    if out.status.success() {
+                        Ok(())
+                    } else {
+                        Err(String::from_utf8_lossy(&out.stderr).trim_end().to_string())
+                    }
+                }
+                Err(e) => Err(e.to_string()),
+            }
+        }
+    };
+
+    BatchResult {
+        status: read_status(repo).await,
+        success: result.is_ok(),
+        error: result.err(),
+        output,
+        path,
+    }
+}
+
+/// Run an operation on several repositories, at most `concurrency` at a time
+///
+/// Results are returned in the order of `paths`.
+pub async fn run_batch(
+    paths: Vec<String>,
+    operation: BatchOperation,
+    concurrency: Option<usize>,
+) -> Result<Vec<BatchResult>, String> {
+    if let BatchOperation::Command { args } = &operation {
+        validate_command(args)?;
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1)));
+    let operation = Arc::new(operation);
+    let mut tasks = JoinSet::new();
+
+    for (index, path) in paths.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        let operation = Arc::clone(&operation);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            (index, run_one(path, &operation).await)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    results.sort_by_key(|(index, _)| *index);
+
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+/// Run an operation on every repository of a workspace
+pub async fn run_workspace_batch(
+    workspace_id: &str,
+    operation: BatchOperation,
+    concurrency: Option<usize>,
+) -> Result<Vec<BatchResult>, String> {
+    let workspaces = load_workspaces().await?;
+    let workspace = workspaces
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    run_batch(workspace.repositories, operation, concurrency).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_command_allow_list() {
+        assert!(validate_command(&args(&["fetch", "--prune"])).is_ok());
+        assert!(validate_command(&args(&["log", "--oneline", "--", "src"])).is_ok());
+        assert!(validate_command(&args(&["maintenance", "start"])).is_err());
+        assert!(validate_command(&args(&["lfs", "pull"])).is_err());
+        assert!(validate_command(&[]).is_err());
+    }
+
+    #[test]
+    fn test_validate_command_only_lists_refs() {
+        assert!(validate_command(&args(&["branch"])).is_ok());
+        assert!(validate_command(&args(&["branch", "-a", "-vv", "--merged=main"])).is_ok());
+        assert!(validate_command(&args(&["branch", "--list", "feature/*"])).is_ok());
+        assert!(validate_command(&args(&["tag", "-l", "v1.*", "--sort=-creatordate"])).is_ok());
+        assert!(validate_command(&args(&["remote", "-v"])).is_ok());
+        assert!(validate_command(&args(&["remote", "get-url", "--push", "origin"])).is_ok());
+
+        assert!(validate_command(&args(&["branch", "-D", "main"])).is_err());
+        assert!(validate_command(&args(&["branch", "--delete", "main"])).is_err());
+        assert!(validate_command(&args(&["branch", "new-branch"])).is_err());
+        assert!(validate_command(&args(&["branch", "-m", "old", "new"])).is_err());
+        assert!(validate_command(&args(&["tag", "-d", "v1.0"])).is_err());
+        assert!(validate_command(&args(&["tag", "v2.0"])).is_err());
+        assert!(validate_command(&args(&["remote", "remove", "origin"])).is_err());
+        assert!(validate_command(&args(&["remote", "set-url", "origin", "x"])).is_err());
+        assert!(validate_command(&args(&["remote", "show", "--prune", "origin"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_command_rejects_abbreviated_options() {
+        assert!(validate_command(&args(&["fetch", "--upload-pack=sh", "/some/path"])).is_err());
+        assert!(validate_command(&args(&["fetch", "--upload=sh -c id", "/some/path"])).is_err());
+        assert!(validate_command(&args(&["fetch", "--upl", "sh", "/some/path"])).is_err());
+        assert!(validate_command(&args(&["log", "--outp=/tmp/x"])).is_err());
+        assert!(validate_command(&args(&["log", "--output-indicator-new=+"])).is_err());
+        assert!(validate_command(&args(&["log", "--no-ext-diff"])).is_ok());
+    }
+}
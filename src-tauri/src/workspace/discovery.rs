@@ -0,0 +1,196 @@
+//! Workspace discovery
+//!
+//! Keeps workspaces bound to root directories in sync with the repositories on disk.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::git;
+use crate::repository;
+use crate::repository::glob::glob_match_any;
+
+use super::persistence::{load_workspaces, update_workspace};
+use super::types::DiscoveryConfig;
+
+/// How often the scheduler checks whether a rescan is due
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+/// Event emitted after a scheduled rescan changed a workspace
+pub const DISCOVERY_EVENT: &str = "workspace-discovery";
+
+/// Changes found by rescanning a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryResult {
+    pub workspace_id: String,
+    pub added: Vec<String>,
+    pub missing: Vec<String>,
+    pub restored: Vec<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Set or clear the discovery roots of a workspace
+pub async fn set_workspace_discovery(workspace_id: &str, discovery: Option<DiscoveryConfig>) -> Result<(), String> {
+    update_workspace(workspace_id, |workspace| {
+        workspace.discovery = discovery;
+        Ok(())
+    })
+    .await
+}
+
+/// Find the repositories under the discovery roots that pass the include and exclude globs
+async fn discover(config: &DiscoveryConfig) -> Vec<String> {
+    let mut found = Vec::new();
+
+    for root in &config.roots {
+        let root_path = Path::new(root);
+        let repos = match repository::scan_repositories(root_path).await {
+            Ok(repos) => repos,
+            Err(_) => continue,
+        };
+
+        for repo in repos {
+            let relative = Path::new(&repo.path)
+                .strip_prefix(root_path)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_else(|_| repo.name.clone());
+
+            if !config.include.is_empty() && !glob_match_any(&config.include, &relative) {
+                continue;
+            }
+            if glob_match_any(&config.exclude, &relative) {
+                continue;
+            }
+            found.push(repo.path);
+        }
+    }
+
+    found
+}
+
+/// Rescan the discovery roots of a workspace
+///
+/// New clones are added unless the user removed them, repositories whose directory
+/// disappeared are flagged as missing, and flagged repositories that came back are
+/// cleared.
+pub async fn rescan_workspace(workspace_id: &str) -> Result<DiscoveryResult, String> {
+    let workspaces = load_workspaces().await?;
+    let workspace = workspaces
+        .iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    let discovered = match &workspace.discovery {
+        Some(config) => discover(config).await,
+        None => Vec::new(),
+    };
+
+    // Probing the disk is slow, so it happens outside the store lock. Check every known
+    // path, not only those under the roots; the diff itself is computed against the
+    // workspace as it is when the update runs.
+    let mut present = HashMap::new();
+    for path in &workspace.repositories {
+        present.insert(path.clone(), git::is_git_repo(Path::new(path)).await);
+    }
+
+    update_workspace(workspace_id, |workspace| {
+        let mut result = DiscoveryResult {
+            workspace_id: workspace.id.clone(),
+            added: Vec::new(),
+            missing: Vec::new(),
+            restored: Vec::new(),
+        };
+
+        for path in discovered {
+            if !workspace.repositories.contains(&path) && !workspace.removed.contains(&path) {
+                workspace.repositories.push(path.clone());
+                result.added.push(path);
+            }
+        }
+
+        // Repositories added since the probe have not been checked and count as present
+        let gone: Vec<String> = workspace
+            .repositories
+            .iter()
+            .filter(|path| present.get(*path) == Some(&false))
+            .cloned()
+            .collect();
+
+        for path in &gone {
+            if !workspace.missing.contains(path) {
+                result.missing.push(path.clone());
+            }
+        }
+        for path in &workspace.missing {
+            if !gone.contains(path) && workspace.repositories.contains(path) {
+                result.restored.push(path.clone());
+            }
+        }
+        workspace.missing = gone;
+
+        if let Some(config) = workspace.discovery.as_mut() {
+            config.last_scan = Some(now_secs());
+        }
+
+        Ok(result)
+    })
+    .await
+}
+
+/// Whether a workspace is due for a rescan
+fn is_due(config: &DiscoveryConfig, startup: bool) -> bool {
+    if startup {
+        return true;
+    }
+    match (config.rescan_interval_minutes, config.last_scan) {
+        (Some(minutes), Some(last)) => now_secs() >= last + u64::from(minutes) * 60,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Rescan every workspace with discovery roots on startup and then on schedule
+///
+/// Emits `DISCOVERY_EVENT` whenever a rescan changes a workspace.
+pub async fn run_discovery_scheduler(app: AppHandle) {
+    let mut startup = true;
+
+    loop {
+        if let Ok(workspaces) = load_workspaces().await {
+            for workspace in workspaces {
+                let due = workspace
+                    .discovery
+                    .as_ref()
+                    .map(|config| is_due(config, startup))
+                    .unwrap_or(false);
+                if !due {
+                    continue;
+                }
+
+                match rescan_workspace(&workspace.id).await {
+                    Ok(result) => {
+                        let changed = !result.added.is_empty()
+                            || !result.missing.is_empty()
+                            || !result.restored.is_empty();
+                        if changed {
+                            let _ = app.emit(DISCOVERY_EVENT, result);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to rescan workspace {}: {}", workspace.name, e),
+                }
+            }
+        }
+
+        startup = false;
+        tokio::time::sleep(SCHEDULER_TICK).await;
+    }
+}
@@ -0,0 +1,170 @@
+//! Workspace identity
+//!
+//! Checks and applies the identity profile of a workspace to its repositories.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::git::config::{list_config, set_config};
+use crate::git::ConfigScope;
+
+use super::persistence::{load_workspaces, update_workspace};
+use super::types::IdentityProfile;
+
+/// A local config value that differs from the workspace profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityMismatch {
+    pub key: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+/// Identity check result for a single repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityCheck {
+    pub path: String,
+    pub matches: bool,
+    pub mismatches: Vec<IdentityMismatch>,
+    pub error: Option<String>,
+}
+
+/// The `core.sshCommand` used to pin a repository to one SSH key
+fn ssh_command(key: &str) -> String {
+    format!("ssh -i \"{}\" -o IdentitiesOnly=yes", key)
+}
+
+/// Local config values a repository needs to match the profile
+fn expected_values(profile: &IdentityProfile) -> Vec<(&'static str, Option<String>)> {
+    vec![
+        ("user.name", Some(profile.name.clone())),
+        ("user.email", Some(profile.email.clone())),
+        ("user.signingkey", profile.signing_key.clone()),
+        ("core.sshcommand", profile.ssh_key.as_deref().map(ssh_command)),
+    ]
+}
+
+/// Compare the local config of a repository with a profile
+async fn check_repository(path: &str, profile: &IdentityProfile) -> IdentityCheck {
+    let entries = match list_config(Path::new(path), Some(ConfigScope::Local)).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            return IdentityCheck {
+                path: path.to_string(),
+                matches: false,
+                mismatches: Vec::new(),
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    let mut mismatches = Vec::new();
+    for (key, expected) in expected_values(profile) {
+        // The last entry wins, as with git itself
+        let actual = entries
+            .iter()
+            .rev()
+            .find(|e| e.key.eq_ignore_ascii_case(key))
+            .map(|e| e.value.clone());
+
+        // Optional profile fields are only checked when set
+        if expected.is_none() {
+            continue;
+        }
+        if actual != expected {
+            mismatches.push(IdentityMismatch {
+                key: key.to_string(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    IdentityCheck {
+        path: path.to_string(),
+        matches: mismatches.is_empty(),
+        mismatches,
+        error: None,
+    }
+}
+
+/// Set or clear the identity profile of a workspace
+pub async fn set_workspace_identity(workspace_id: &str, identity: Option<IdentityProfile>) -> Result<(), String> {
+    update_workspace(workspace_id, |workspace| {
+        workspace.identity = identity;
+        Ok(())
+    })
+    .await
+}
+
+/// Check every repository in a workspace against its identity profile
+pub async fn check_workspace_identity(workspace_id: &str) -> Result<Vec<IdentityCheck>, String> {
+    let workspaces = load_workspaces().await?;
+    let workspace = workspaces
+        .iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    let profile = match &workspace.identity {
+        Some(profile) => profile,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut results = Vec::new();
+    for path in &workspace.repositories {
+        results.push(check_repository(path, profile).await);
+    }
+    Ok(results)
+}
+
+/// Write the workspace identity into the local config of its repositories
+///
+/// Only `paths` are touched when given, otherwise every repository in the workspace.
+pub async fn apply_workspace_identity(
+    workspace_id: &str,
+    paths: Option<Vec<String>>,
+) -> Result<Vec<IdentityCheck>, String> {
+    let workspaces = load_workspaces().await?;
+    let workspace = workspaces
+        .iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    let profile = workspace
+        .identity
+        .as_ref()
+        .ok_or("Workspace has no identity profile")?;
+
+    let targets: Vec<&String> = workspace
+        .repositories
+        .iter()
+        .filter(|p| paths.as_ref().map(|only| only.contains(p)).unwrap_or(true))
+        .collect();
+
+    let mut results = Vec::new();
+    for path in targets {
+        let repo = Path::new(path);
+        let mut error = None;
+
+        // Values the profile leaves unset are not touched
+        for (key, expected) in expected_values(profile) {
+            let Some(value) = expected else { continue };
+            if let Err(e) = set_config(repo, key, &value, ConfigScope::Local).await {
+                error = Some(e.to_string());
+                break;
+            }
+        }
+
+        match error {
+            Some(error) => results.push(IdentityCheck {
+                path: path.clone(),
+                matches: false,
+                mismatches: Vec::new(),
+                error: Some(error),
+            }),
+            None => results.push(check_repository(path, profile).await),
+        }
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,108 @@
+//! Workspace config migrations
+//!
+//! Upgrades older `workspaces.json` layouts to the current schema, one version at a time.
+
+use serde_json::Value;
+
+/// Schema version written by this build
+pub const CURRENT_VERSION: u32 = 6;
+
+/// A migration from version `n` to `n + 1`
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/// Migrations indexed by the version they upgrade from
+const MIGRATIONS: &[(u32, Migration)] = &[
+    (1, migrate_v1_to_v2),
+    (2, migrate_v2_to_v3),
+    (3, migrate_v3_to_v4),
+    (4, migrate_v4_to_v5),
+    (5, migrate_v5_to_v6),
+];
+
+/// Bring a raw config up to `CURRENT_VERSION`
+///
+/// Returns an error for configs written by a newer build, so they are never overwritten.
+pub fn migrate(config: &mut Value) -> Result<(), String> {
+    let mut version = config
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or(1) as u32;
+
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Workspaces were saved by a newer version of Pinax (schema {}, supported {})",
+            version, CURRENT_VERSION
+        ));
+    }
+
+    while version < CURRENT_VERSION {
+        let (_, migration) = MIGRATIONS
+            .iter()
+            .find(|(from, _)| *from == version)
+            .ok_or_else(|| format!("No migration from workspace schema {}", version))?;
+
+        migration(config)?;
+        version += 1;
+        config["version"] = Value::from(version);
+    }
+
+    Ok(())
+}
+
+/// Apply a change to every workspace object in the config
+fn for_each_workspace<F>(config: &mut Value, mut change: F) -> Result<(), String>
+where
+    F: FnMut(&mut serde_json::Map<String, Value>),
+{
+    let workspaces = config
+        .get_mut("workspaces")
+        .and_then(Value::as_array_mut)
+        .ok_or("Workspace config has no workspace list")?;
+
+    for workspace in workspaces {
+        let workspace = workspace
+            .as_object_mut()
+            .ok_or("Workspace entry is not an object")?;
+        change(workspace);
+    }
+
+    Ok(())
+}
+
+/// v2 adds identity profiles, repository aliases and pins to each workspace
+fn migrate_v1_to_v2(config: &mut Value) -> Result<(), String> {
+    for_each_workspace(config, |workspace| {
+        workspace.entry("identity").or_insert(Value::Null);
+        workspace.entry("aliases").or_insert_with(|| Value::Object(Default::default()));
+        workspace.entry("pinned").or_insert_with(|| Value::Array(Vec::new()));
+    })
+}
+
+/// v3 adds discovery roots and the list of missing repositories
+fn migrate_v2_to_v3(config: &mut Value) -> Result<(), String> {
+    for_each_workspace(config, |workspace| {
+        workspace.entry("discovery").or_insert(Value::Null);
+        workspace.entry("missing").or_insert_with(|| Value::Array(Vec::new()));
+    })
+}
+
+/// v4 adds background fetch settings
+fn migrate_v3_to_v4(config: &mut Value) -> Result<(), String> {
+    for_each_workspace(config, |workspace| {
+        workspace.entry("auto_fetch").or_insert(Value::Null);
+    })
+}
+
+/// v5 adds branch name templates
+fn migrate_v4_to_v5(config: &mut Value) -> Result<(), String> {
+    for_each_workspace(config, |workspace| {
+        workspace.entry("branch_templates").or_insert_with(|| Value::Array(Vec::new()));
+    })
+}
+
+/// v6 remembers repositories removed by the user so discovery leaves them out
+fn migrate_v5_to_v6(config: &mut Value) -> Result<(), String> {
+    for_each_workspace(config, |workspace| {
+        workspace.entry("removed").or_insert_with(|| Value::Array(Vec::new()));
+    })
+}
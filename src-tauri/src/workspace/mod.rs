@@ -2,8 +2,26 @@
 //!
 //! Logical grouping of repositories with persistence.
 
+pub mod autofetch;
+pub mod batch;
+pub mod discovery;
+pub mod identity;
+pub mod migrations;
 pub mod persistence;
+pub mod portable;
 pub mod types;
 
-pub use persistence::{add_repository_to_workspace, create_workspace, load_workspaces, delete_workspace};
+pub use persistence::{
+    add_repository_to_workspace, create_workspace, load_workspaces, delete_workspace, rename_workspace,
+    set_workspace_color, remove_repository_from_workspace, reorder_repositories,
+    move_repository_between_workspaces, set_repository_alias, set_repository_pinned, set_workspace_branch_templates,
+};
+pub use autofetch::{
+    run_auto_fetch_scheduler, set_network_online, set_open_repositories, set_repository_auto_fetch,
+    set_workspace_auto_fetch,
+};
+pub use batch::{run_batch, run_workspace_batch, BatchOperation, BatchResult};
+pub use discovery::{rescan_workspace, run_discovery_scheduler, set_workspace_discovery, DiscoveryResult};
+pub use identity::{apply_workspace_identity, check_workspace_identity, set_workspace_identity, IdentityCheck};
+pub use portable::{export_workspace, import_workspace, plan_workspace_import, ImportPlan, ImportResult};
 pub use types::*;
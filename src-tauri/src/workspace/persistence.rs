@@ -2,11 +2,17 @@
 //!
 //! Handles loading and saving workspaces to disk.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
 
+use super::migrations::{migrate, CURRENT_VERSION};
 use super::types::{Workspace, WorkspaceConfig};
 
+/// Serializes every read-modify-write of the config file within the process
+static STORE_LOCK: Mutex<()> = Mutex::const_new(());
+
 /// Get the path to the workspaces configuration file
 fn get_config_path() -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir().ok_or("Could not find config directory")?;
@@ -14,26 +20,76 @@ fn get_config_path() -> Result<PathBuf, String> {
     Ok(app_dir.join("workspaces.json"))
 }
 
-/// Load workspaces from disk
-pub async fn load_workspaces() -> Result<Vec<Workspace>, String> {
+/// Path of a sibling file next to the config, e.g. `workspaces.json.bak`
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Why a config file could not be used
+enum ConfigError {
+    /// Not valid JSON or not a valid config; the backup can stand in for it
+    Corrupt(String),
+    /// Written by a newer schema (or not migratable) and must be left untouched
+    Incompatible(String),
+}
+
+impl From<ConfigError> for String {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::Corrupt(message) | ConfigError::Incompatible(message) => message,
+        }
+    }
+}
+
+/// Parse and migrate a config file
+fn parse_config(content: &str) -> Result<WorkspaceConfig, ConfigError> {
+    let mut raw: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| ConfigError::Corrupt(format!("Failed to parse workspaces: {}", e)))?;
+
+    migrate(&mut raw).map_err(ConfigError::Incompatible)?;
+
+    serde_json::from_value(raw).map_err(|e| ConfigError::Corrupt(format!("Failed to parse workspaces: {}", e)))
+}
+
+/// Read the config, falling back to the backup if the main file is corrupt
+///
+/// A config from a newer schema is reported as an error rather than replaced by the
+/// backup, so the following write cannot discard it.
+async fn read_config() -> Result<WorkspaceConfig, String> {
     let path = get_config_path()?;
+    let backup = sibling_path(&path, "bak");
 
     if !path.exists() {
-        return Ok(Vec::new());
+        if backup.exists() {
+            let content = fs::read_to_string(&backup)
+                .await
+                .map_err(|e| format!("Failed to read workspace backup: {}", e))?;
+            return Ok(parse_config(&content)?);
+        }
+        return Ok(WorkspaceConfig::default());
     }
 
     let content = fs::read_to_string(&path)
         .await
         .map_err(|e| format!("Failed to read workspaces: {}", e))?;
 
-    let config: WorkspaceConfig =
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse workspaces: {}", e))?;
-
-    Ok(config.workspaces)
+    match parse_config(&content) {
+        Ok(config) => Ok(config),
+        Err(ConfigError::Corrupt(_)) if backup.exists() => {
+            let content = fs::read_to_string(&backup)
+                .await
+                .map_err(|e| format!("Failed to read workspace backup: {}", e))?;
+            Ok(parse_config(&content)?)
+        }
+        Err(e) => Err(e.into()),
+    }
 }
 
-/// Save workspaces to disk
-pub async fn save_workspaces(workspaces: Vec<Workspace>) -> Result<(), String> {
+/// Write the config through a temp file and rename, keeping a backup of the previous file
+async fn write_config(workspaces: Vec<Workspace>) -> Result<(), String> {
     let path = get_config_path()?;
 
     // Ensure parent directory exists
@@ -44,27 +100,93 @@ pub async fn save_workspaces(workspaces: Vec<Workspace>) -> Result<(), String> {
     }
 
     let config = WorkspaceConfig {
-        version: 1,
+        version: CURRENT_VERSION,
         workspaces,
     };
 
     let content =
         serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize: {}", e))?;
 
-    fs::write(&path, content)
+    let tmp = sibling_path(&path, "tmp");
+    let mut file = fs::File::create(&tmp)
+        .await
+        .map_err(|e| format!("Failed to write workspaces: {}", e))?;
+    file.write_all(content.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write workspaces: {}", e))?;
+    file.sync_all()
+        .await
+        .map_err(|e| format!("Failed to write workspaces: {}", e))?;
+    drop(file);
+
+    // Only a file that still parses is worth keeping as the backup, and a file from a
+    // newer schema is never replaced
+    if let Ok(previous) = fs::read_to_string(&path).await {
+        match parse_config(&previous) {
+            Ok(_) => {
+                fs::write(sibling_path(&path, "bak"), previous)
+                    .await
+                    .map_err(|e| format!("Failed to back up workspaces: {}", e))?;
+            }
+            Err(ConfigError::Incompatible(message)) => {
+                let _ = fs::remove_file(&tmp).await;
+                return Err(message);
+            }
+            Err(ConfigError::Corrupt(_)) => {}
+        }
+    }
+
+    fs::rename(&tmp, &path)
         .await
         .map_err(|e| format!("Failed to write workspaces: {}", e))?;
 
     Ok(())
 }
 
+/// Load workspaces from disk
+pub async fn load_workspaces() -> Result<Vec<Workspace>, String> {
+    let _guard = STORE_LOCK.lock().await;
+    Ok(read_config().await?.workspaces)
+}
+
+/// Apply a change to all workspaces and save the result
+///
+/// The store lock is held from load to save, so concurrent updates never overwrite
+/// each other. Nothing is written when `change` fails.
+pub async fn update_workspaces<T, F>(change: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Vec<Workspace>) -> Result<T, String>,
+{
+    let _guard = STORE_LOCK.lock().await;
+    let mut workspaces = read_config().await?.workspaces;
+    let result = change(&mut workspaces)?;
+    write_config(workspaces).await?;
+    Ok(result)
+}
+
+/// Apply a change to a single workspace and save the result
+pub async fn update_workspace<T, F>(workspace_id: &str, change: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Workspace) -> Result<T, String>,
+{
+    update_workspaces(|workspaces| {
+        let workspace = workspaces
+            .iter_mut()
+            .find(|w| w.id == workspace_id)
+            .ok_or("Workspace not found")?;
+        change(workspace)
+    })
+    .await
+}
+
 /// Create a new workspace
 pub async fn create_workspace(name: String) -> Result<Workspace, String> {
-    let mut workspaces = load_workspaces().await?;
-    let workspace = Workspace::new(name);
-    workspaces.push(workspace.clone());
-    save_workspaces(workspaces).await?;
-    Ok(workspace)
+    update_workspaces(|workspaces| {
+        let workspace = Workspace::new(name);
+        workspaces.push(workspace.clone());
+        Ok(workspace)
+    })
+    .await
 }
 
 /// Add a repository to a workspace
@@ -72,30 +194,189 @@ pub async fn add_repository_to_workspace(
     workspace_id: &str,
     repo_path: &str,
 ) -> Result<(), String> {
-    let mut workspaces = load_workspaces().await?;
-
-    let workspace = workspaces
-        .iter_mut()
-        .find(|w| w.id == workspace_id)
-        .ok_or("Workspace not found")?;
-
-    if !workspace.repositories.contains(&repo_path.to_string()) {
-        workspace.repositories.push(repo_path.to_string());
-    }
-
-    save_workspaces(workspaces).await?;
-    Ok(())
+    update_workspace(workspace_id, |workspace| {
+        if !workspace.repositories.contains(&repo_path.to_string()) {
+            workspace.repositories.push(repo_path.to_string());
+        }
+        workspace.removed.retain(|p| p != repo_path);
+        Ok(())
+    })
+    .await
 }
 
 /// Delete a workspace
 pub async fn delete_workspace(workspace_id: String) -> Result<(), String> {
-    let mut workspaces = load_workspaces().await?;
-    
-    if let Some(pos) = workspaces.iter().position(|w| w.id == workspace_id) {
+    update_workspaces(|workspaces| {
+        let pos = workspaces
+            .iter()
+            .position(|w| w.id == workspace_id)
+            .ok_or("Workspace not found")?;
         workspaces.remove(pos);
-        save_workspaces(workspaces).await?;
         Ok(())
-    } else {
-        Err("Workspace not found".to_string())
+    })
+    .await
+}
+
+/// Rename a workspace
+pub async fn rename_workspace(workspace_id: &str, name: String) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Workspace name cannot be empty".to_string());
     }
+
+    update_workspace(workspace_id, |w| {
+        w.name = name;
+        Ok(())
+    })
+    .await
+}
+
+/// Set or clear the color of a workspace
+pub async fn set_workspace_color(workspace_id: &str, color: Option<String>) -> Result<(), String> {
+    update_workspace(workspace_id, |w| {
+        w.color = color.filter(|c| !c.trim().is_empty());
+        Ok(())
+    })
+    .await
+}
+
+/// Remove a repository from a workspace, along with its alias and pin
+///
+/// The path is remembered so that discovery does not add it back.
+pub async fn remove_repository_from_workspace(workspace_id: &str, repo_path: &str) -> Result<(), String> {
+    update_workspace(workspace_id, |w| {
+        let before = w.repositories.len();
+        w.repositories.retain(|p| p != repo_path);
+        if w.repositories.len() == before {
+            return Err("Repository not found in workspace".to_string());
+        }
+        w.aliases.remove(repo_path);
+        w.pinned.retain(|p| p != repo_path);
+        w.missing.retain(|p| p != repo_path);
+        if !w.removed.iter().any(|p| p == repo_path) {
+            w.removed.push(repo_path.to_string());
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Reorder the repositories of a workspace
+///
+/// `order` must contain exactly the repositories already in the workspace.
+pub async fn reorder_repositories(workspace_id: &str, order: Vec<String>) -> Result<(), String> {
+    update_workspace(workspace_id, |w| {
+        let mut current = w.repositories.clone();
+        let mut requested = order.clone();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Err("New order must contain exactly the repositories of the workspace".to_string());
+        }
+        w.repositories = order;
+        Ok(())
+    })
+    .await
+}
+
+/// Move a repository from one workspace to another
+///
+/// The alias and pin travel with the repository. It is inserted at `index`, or
+/// appended when no index is given.
+pub async fn move_repository_between_workspaces(
+    from_id: &str,
+    to_id: &str,
+    repo_path: &str,
+    index: Option<usize>,
+) -> Result<(), String> {
+    update_workspaces(|workspaces| {
+        let from = workspaces
+            .iter_mut()
+            .find(|w| w.id == from_id)
+            .ok_or("Source workspace not found")?;
+
+        let pos = from
+            .repositories
+            .iter()
+            .position(|p| p == repo_path)
+            .ok_or("Repository not found in source workspace")?;
+        from.repositories.remove(pos);
+        let alias = from.aliases.remove(repo_path);
+        let was_pinned = from.pinned.iter().any(|p| p == repo_path);
+        from.pinned.retain(|p| p != repo_path);
+        if !from.removed.iter().any(|p| p == repo_path) {
+            from.removed.push(repo_path.to_string());
+        }
+
+        let to = workspaces
+            .iter_mut()
+            .find(|w| w.id == to_id)
+            .ok_or("Target workspace not found")?;
+
+        if !to.repositories.iter().any(|p| p == repo_path) {
+            let index = index.unwrap_or(to.repositories.len()).min(to.repositories.len());
+            to.repositories.insert(index, repo_path.to_string());
+        }
+        to.removed.retain(|p| p != repo_path);
+        if let Some(alias) = alias {
+            to.aliases.entry(repo_path.to_string()).or_insert(alias);
+        }
+        if was_pinned && !to.pinned.iter().any(|p| p == repo_path) {
+            to.pinned.push(repo_path.to_string());
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+/// Set or clear the display alias of a repository in a workspace
+pub async fn set_repository_alias(workspace_id: &str, repo_path: &str, alias: Option<String>) -> Result<(), String> {
+    update_workspace(workspace_id, |w| {
+        if !w.repositories.iter().any(|p| p == repo_path) {
+            return Err("Repository not found in workspace".to_string());
+        }
+        match alias.map(|a| a.trim().to_string()).filter(|a| !a.is_empty()) {
+            Some(alias) => {
+                w.aliases.insert(repo_path.to_string(), alias);
+            }
+            None => {
+                w.aliases.remove(repo_path);
+            }
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Pin or unpin a repository in a workspace
+pub async fn set_repository_pinned(workspace_id: &str, repo_path: &str, pinned: bool) -> Result<(), String> {
+    update_workspace(workspace_id, |w| {
+        if !w.repositories.iter().any(|p| p == repo_path) {
+            return Err("Repository not found in workspace".to_string());
+        }
+        let is_pinned = w.pinned.iter().any(|p| p == repo_path);
+        if pinned && !is_pinned {
+            w.pinned.push(repo_path.to_string());
+        } else if !pinned {
+            w.pinned.retain(|p| p != repo_path);
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Set the branch name templates of a workspace
+pub async fn set_workspace_branch_templates(workspace_id: &str, templates: Vec<String>) -> Result<(), String> {
+    let templates: Vec<String> = templates
+        .into_iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    update_workspace(workspace_id, |w| {
+        w.branch_templates = templates;
+        Ok(())
+    })
+    .await
 }
@@ -0,0 +1,394 @@
+//! Workspace import and export
+//!
+//! Portable workspace definitions that carry remote URLs and relative layout
+//! instead of absolute paths, so they can be shared between machines.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::git;
+use crate::repository;
+
+use super::persistence::{load_workspaces, update_workspaces};
+use super::types::Workspace;
+
+/// Marker identifying a Pinax workspace file
+const EXPORT_FORMAT: &str = "pinax-workspace";
+
+/// Version of the export file layout
+const EXPORT_VERSION: u32 = 1;
+
+/// A workspace definition without machine-specific paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceExport {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    pub color: Option<String>,
+    pub repositories: Vec<ExportedRepository>,
+}
+
+/// A repository entry in an exported workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedRepository {
+    pub name: String,
+    pub remote_url: Option<String>,
+    /// Location relative to the common root of the exported repositories
+    pub relative_path: String,
+    /// Original location, set only when the repositories share no common root (e.g. they
+    /// live on different drives); `relative_path` is then just a directory name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absolute_path: Option<String>,
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// How an imported repository will be resolved locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    /// An existing clone with the same remote was found
+    Matched,
+    /// No local clone exists; it can be cloned into `target_path`
+    Missing,
+    /// The entry has no remote URL, so it can neither be matched nor cloned
+    NoRemote,
+}
+
+/// Planned resolution of one repository during import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEntry {
+    pub name: String,
+    pub remote_url: Option<String>,
+    pub relative_path: String,
+    pub status: ImportStatus,
+    pub local_path: Option<String>,
+    pub target_path: String,
+}
+
+/// What importing a workspace file into a root directory would do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPlan {
+    pub name: String,
+    pub color: Option<String>,
+    pub entries: Vec<ImportEntry>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub workspace: Workspace,
+    pub cloned: Vec<String>,
+    pub failed: Vec<ImportFailure>,
+}
+
+/// A repository that could not be cloned during import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Normalize a remote URL so SSH and HTTPS forms of the same repository compare equal
+///
+/// `git@github.com:owner/repo.git` and `https://user@github.com/owner/repo` both
+/// become `github.com/owner/repo`.
+pub fn normalize_remote_url(url: &str) -> String {
+    let mut url = url.trim().trim_end_matches('/').to_string();
+
+    if let Some(pos) = url.find("://") {
+        url = url[pos + 3..].to_string();
+    } else if let Some((host, path)) = url.split_once(':') {
+        // scp-like syntax: [user@]host:path
+        if !host.contains('/') {
+            url = format!("{}/{}", host, path.trim_start_matches('/'));
+        }
+    }
+
+    // Drop credentials or the ssh user
+    if let Some(pos) = url.find('@') {
+        if url[..pos].find('/').is_none() {
+            url = url[pos + 1..].to_string();
+        }
+    }
+
+    let url = url.strip_suffix(".git").unwrap_or(&url);
+    match url.split_once('/') {
+        Some((host, path)) => {
+            // Ports are irrelevant for matching
+            let host = host.split(':').next().unwrap_or(host);
+            format!("{}/{}", host.to_lowercase(), path)
+        }
+        None => url.to_lowercase(),
+    }
+}
+
+/// Longest directory shared by all paths, if any
+fn common_root(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut iter = paths.iter();
+    let first = iter.next()?;
+    let mut root: PathBuf = first.parent().unwrap_or(first).to_path_buf();
+
+    for path in iter {
+        while !path.starts_with(&root) {
+            root = root.parent()?.to_path_buf();
+        }
+    }
+
+    Some(root)
+}
+
+/// Reject relative paths that would escape the import root
+fn safe_relative_path(relative: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(relative.replace('\\', "/"));
+    let safe = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !safe || relative.trim().is_empty() {
+        return Err(format!("Invalid repository path in workspace file: {}", relative));
+    }
+    Ok(path)
+}
+
+/// Build the portable definition of a workspace
+pub async fn build_workspace_export(workspace_id: &str) -> Result<WorkspaceExport, String> {
+    let workspaces = load_workspaces().await?;
+    let workspace = workspaces
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or("Workspace not found")?;
+
+    let paths: Vec<PathBuf> = workspace.repositories.iter().map(PathBuf::from).collect();
+    let root = common_root(&paths);
+
+    let mut repositories = Vec::new();
+    let mut used_names: Vec<String> = Vec::new();
+    for path in &paths {
+        let key = path.to_string_lossy().to_string();
+        let info = repository::get_repository_info(path).await.ok();
+
+        let name = info
+            .as_ref()
+            .map(|r| r.name.clone())
+            .or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let relative = root
+            .as_ref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .filter(|p| !p.as_os_str().is_empty());
+
+        let (relative_path, absolute_path) = match relative {
+            Some(relative) => (relative.to_string_lossy().replace('\\', "/"), None),
+            // Without a shared root, fall back to a unique directory name per repository
+            // and keep the original location explicitly
+            None => {
+                let base = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| name.clone());
+                let mut unique = base.clone();
+                let mut n = 2;
+                while used_names.contains(&unique) {
+                    unique = format!("{}-{}", base, n);
+                    n += 1;
+                }
+                (unique, Some(key.clone()))
+            }
+        };
+        used_names.push(relative_path.clone());
+
+        repositories.push(ExportedRepository {
+            name,
+            remote_url: info.and_then(|r| r.remote_url),
+            relative_path,
+            absolute_path,
+            alias: workspace.aliases.get(&key).cloned(),
+            pinned: workspace.pinned.contains(&key),
+        });
+    }
+
+    Ok(WorkspaceExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        name: workspace.name,
+        color: workspace.color,
+        repositories,
+    })
+}
+
+/// Export a workspace to a JSON file
+pub async fn export_workspace(workspace_id: &str, file: &Path) -> Result<(), String> {
+    let export = build_workspace_export(workspace_id).await?;
+    let content =
+        serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize: {}", e))?;
+
+    fs::write(file, content)
+        .await
+        .map_err(|e| format!("Failed to write workspace file: {}", e))
+}
+
+/// Read and validate a workspace file
+async fn read_workspace_file(file: &Path) -> Result<WorkspaceExport, String> {
+    let content = fs::read_to_string(file)
+        .await
+        .map_err(|e| format!("Failed to read workspace file: {}", e))?;
+
+    let export: WorkspaceExport =
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse workspace file: {}", e))?;
+
+    if export.format != EXPORT_FORMAT {
+        return Err("Not a Pinax workspace file".to_string());
+    }
+    if export.version > EXPORT_VERSION {
+        return Err(format!(
+            "Workspace file version {} is newer than supported version {}",
+            export.version, EXPORT_VERSION
+        ));
+    }
+
+    Ok(export)
+}
+
+/// Work out which repositories of a workspace file already exist locally
+///
+/// Clones are matched by remote URL among the repositories of existing workspaces
+/// and those found under `root`.
+pub async fn plan_workspace_import(file: &Path, root: &Path) -> Result<ImportPlan, String> {
+    let export = read_workspace_file(file).await?;
+
+    // Known local clones, keyed by normalized remote URL
+    let mut known: Vec<(String, String)> = Vec::new();
+    for workspace in load_workspaces().await? {
+        for path in workspace.repositories {
+            if let Ok(info) = repository::get_repository_info(Path::new(&path)).await {
+                if let Some(url) = info.remote_url {
+                    known.push((normalize_remote_url(&url), info.path));
+                }
+            }
+        }
+    }
+    if root.is_dir() {
+        for repo in repository::scan_repositories(root).await? {
+            if let Some(url) = repo.remote_url {
+                known.push((normalize_remote_url(&url), repo.path));
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    for repo in export.repositories {
+        let relative = safe_relative_path(&repo.relative_path)?;
+        let target_path = root.join(relative).to_string_lossy().to_string();
+
+        let mut local_path = repo.remote_url.as_ref().and_then(|url| {
+            let normalized = normalize_remote_url(url);
+            known.iter().find(|(u, _)| *u == normalized).map(|(_, path)| path.clone())
+        });
+        // An absolute location only helps when importing on the machine it came from
+        if local_path.is_none() {
+            if let Some(absolute) = &repo.absolute_path {
+                if git::is_git_repo(Path::new(absolute)).await {
+                    local_path = Some(absolute.clone());
+                }
+            }
+        }
+
+        let status = match (&local_path, &repo.remote_url) {
+            (Some(_), _) => ImportStatus::Matched,
+            (None, Some(_)) => ImportStatus::Missing,
+            (None, None) => ImportStatus::NoRemote,
+        };
+
+        entries.push(ImportEntry {
+            name: repo.name,
+            remote_url: repo.remote_url,
+            relative_path: repo.relative_path,
+            status,
+            local_path,
+            target_path,
+        });
+    }
+
+    Ok(ImportPlan {
+        name: export.name,
+        color: export.color,
+        entries,
+    })
+}
+
+/// Import a workspace file, optionally cloning missing repositories into `root`
+pub async fn import_workspace(file: &Path, root: &Path, clone_missing: bool) -> Result<ImportResult, String> {
+    let export = read_workspace_file(file).await?;
+    let plan = plan_workspace_import(file, root).await?;
+
+    let mut workspace = Workspace::new(plan.name);
+    workspace.color = plan.color;
+
+    let mut cloned = Vec::new();
+    let mut failed = Vec::new();
+
+    for (entry, exported) in plan.entries.into_iter().zip(export.repositories) {
+        let path = match (entry.status, entry.local_path) {
+            (ImportStatus::Matched, Some(path)) => path,
+            (ImportStatus::Missing, _) if clone_missing => {
+                let url = entry.remote_url.unwrap_or_default();
+                let target = PathBuf::from(&entry.target_path);
+
+                // Never clone over an existing, non-empty directory
+                let occupied = match fs::read_dir(&target).await {
+                    Ok(mut entries) => matches!(entries.next_entry().await, Ok(Some(_))),
+                    Err(_) => false,
+                };
+                if occupied {
+                    failed.push(ImportFailure {
+                        name: entry.name,
+                        error: format!("{} already exists and is not empty", entry.target_path),
+                    });
+                    continue;
+                }
+
+                match git::clone(&url, &target).await {
+                    Ok(()) => {
+                        cloned.push(entry.target_path.clone());
+                        entry.target_path
+                    }
+                    Err(e) => {
+                        failed.push(ImportFailure {
+                            name: entry.name,
+                            error: e.to_string(),
+                        });
+                        continue;
+                    }
+                }
+            }
+            _ => continue,
+        };
+
+        if let Some(alias) = exported.alias {
+            workspace.aliases.insert(path.clone(), alias);
+        }
+        if exported.pinned {
+            workspace.pinned.push(path.clone());
+        }
+        if !workspace.repositories.contains(&path) {
+            workspace.repositories.push(path);
+        }
+    }
+
+    let saved = workspace.clone();
+    update_workspaces(|workspaces| {
+        workspaces.push(saved);
+        Ok(())
+    })
+    .await?;
+
+    Ok(ImportResult {
+        workspace,
+        cloned,
+        failed,
+    })
+}
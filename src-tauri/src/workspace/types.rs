@@ -1,5 +1,7 @@
 //! Workspace types
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,6 +13,76 @@ pub struct Workspace {
     pub repositories: Vec<String>, // Paths to repositories
     pub color: Option<String>,
     pub created_at: String,
+    #[serde(default)]
+    pub identity: Option<IdentityProfile>,
+    /// Display names keyed by repository path
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Repository paths pinned to the top of the list
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// Root directories rescanned to keep the repository list up to date
+    #[serde(default)]
+    pub discovery: Option<DiscoveryConfig>,
+    /// Repositories whose directory no longer holds a Git repository
+    #[serde(default)]
+    pub missing: Vec<String>,
+    /// Repositories the user removed, which discovery must not add back
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Background fetch settings
+    #[serde(default)]
+    pub auto_fetch: Option<AutoFetchConfig>,
+    /// Branch name templates such as `feature/{ticket}-{slug}`
+    #[serde(default)]
+    pub branch_templates: Vec<String>,
+}
+
+/// Automatic repository discovery settings of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    pub roots: Vec<String>,
+    /// Globs relative to a root; an empty list includes everything
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Minutes between scheduled rescans, `None` only rescans on startup
+    #[serde(default)]
+    pub rescan_interval_minutes: Option<u32>,
+    /// Unix timestamp of the last completed scan
+    #[serde(default)]
+    pub last_scan: Option<u64>,
+}
+
+/// Background fetch settings of a workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoFetchConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// Repository paths opted out of background fetches
+    #[serde(default)]
+    pub excluded: Vec<String>,
+}
+
+impl Default for AutoFetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 15,
+            excluded: Vec::new(),
+        }
+    }
+}
+
+/// Git identity that every repository in a workspace should commit with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityProfile {
+    pub name: String,
+    pub email: String,
+    pub signing_key: Option<String>,
+    /// Private key used for SSH remotes, applied through `core.sshCommand`
+    pub ssh_key: Option<String>,
 }
 
 impl Workspace {
@@ -21,6 +93,14 @@ impl Workspace {
             repositories: Vec::new(),
             color: None,
             created_at: chrono_now(),
+            identity: None,
+            aliases: HashMap::new(),
+            pinned: Vec::new(),
+            discovery: None,
+            missing: Vec::new(),
+            removed: Vec::new(),
+            auto_fetch: None,
+            branch_templates: Vec::new(),
         }
     }
 }
@@ -2,15 +2,16 @@ import { MainLayout } from "@/components/layout";
 import { QuickSearch } from "@/components/QuickSearch";
 import { ShortcutsModal } from "@/components/modals/ShortcutsModal";
 import { GitConfigModal } from "@/components/modals/GitConfigModal";
-import { useKeyBindings, useTheme } from "@/hooks";
+import { useAutoFetch, useKeyBindings, useTheme } from "@/hooks";
 import { useAppStore } from "@/stores/appStore";
 import React from "react";
 import "./index.css";
 
 export default function App() {
-  // Initialize keybinding engine and theme
+  // Initialize keybinding engine, theme and background fetch
   useKeyBindings();
   useTheme();
+  useAutoFetch();
   const { loadAvailableEditors } = useAppStore();
 
   React.useEffect(() => {
@@ -88,6 +88,8 @@ export function MainArea() {
     } = useAppStore();
 
     const [commitMessage, setCommitMessage] = React.useState("");
+    // Bare repositories are browsed read-only; the backend rejects write actions
+    const readOnly = repositoryStatus?.read_only ?? false;
     const [isCommitted, setIsCommitted] = React.useState(false);
     const [activeTab, setActiveTab] = React.useState<"changes" | "history">("changes");
     const [branchSelectorOpen, setBranchSelectorOpen] = React.useState(false);
@@ -520,7 +522,7 @@ export function MainArea() {
                                                         <div className="flex items-center justify-between gap-4">
                                                             <div className="flex items-center gap-3">
                                                                 <span className="text-[9px] font-mono font-black text-primary/40 group-hover:text-primary transition-colors bg-primary/5 px-2 py-0.5 rounded-full border border-primary/10">{commit.short_hash}</span>
-                                                                {index === 0 && (
+                                                                {index === 0 && !readOnly && (
                                                                     <button
                                                                         onClick={(e) => {
                                                                             e.stopPropagation();
@@ -546,6 +548,7 @@ export function MainArea() {
                                                     </div>
                                                 </ContextMenuTrigger>
                                                 <ContextMenuContent className="w-64 bg-card border-border rounded-none shadow-2xl">
+                                                    {!readOnly && (<>
                                                     <ContextMenuItem
                                                         className="flex items-center gap-3 py-2.5 text-xs font-bold cursor-pointer"
                                                         onClick={() => {
@@ -585,6 +588,7 @@ export function MainArea() {
                                                         <GitBranchPlus className="w-3.5 h-3.5" />
                                                         Cherry-pick commit...
                                                     </ContextMenuItem>
+                                                    </>)}
                                                     <ContextMenuItem
                                                         className="flex items-center gap-3 py-2.5 text-xs font-bold cursor-pointer"
                                                         onClick={() => {
@@ -627,6 +631,11 @@ export function MainArea() {
                     </div>
 
                     {/* Commit Box at bottom of left column */}
+                    {readOnly ? (
+                        <div className="p-4 border-t border-border/10 bg-card/40 text-center text-[10px] text-muted-foreground/70 font-black uppercase tracking-[0.2em]">
+                            Bare repository · read-only
+                        </div>
+                    ) : (
                     <div className="p-4 border-t border-border/10 bg-card/40 backdrop-blur-xl">
                         <textarea
                             ref={commitInputRef}
@@ -660,6 +669,7 @@ export function MainArea() {
                             </button>
                         </div>
                     </div>
+                    )}
                 </div>
 
                 {/* Optional middle column for commit details (files list) */}
@@ -1,3 +1,4 @@
 export { useKeyBindings } from "./useKeyBindings";
 export { useAutoRefresh } from "./useAutoRefresh";
+export { useAutoFetch } from "./useAutoFetch";
 export { useTheme } from "./useTheme";
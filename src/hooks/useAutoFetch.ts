@@ -0,0 +1,29 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useAppStore } from "@/stores/appStore";
+import type { AutoFetchEvent } from "@/types";
+
+/**
+ * Hook to connect the background fetcher to the UI
+ * Reports online/offline changes and reacts to "auto-fetch" events
+ */
+export function useAutoFetch() {
+    const { setNetworkOnline, handleAutoFetchEvent } = useAppStore();
+
+    useEffect(() => {
+        const update = () => setNetworkOnline(navigator.onLine);
+        update();
+        window.addEventListener("online", update);
+        window.addEventListener("offline", update);
+
+        const unlisten = listen<AutoFetchEvent>("auto-fetch", (event) => {
+            handleAutoFetchEvent(event.payload);
+        });
+
+        return () => {
+            window.removeEventListener("online", update);
+            window.removeEventListener("offline", update);
+            unlisten.then((stop) => stop());
+        };
+    }, [setNetworkOnline, handleAutoFetchEvent]);
+}
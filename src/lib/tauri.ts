@@ -4,13 +4,20 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { Repository, RepositoryStatus, Branch, Workspace, GitHubRepo, CommitInfo, FileChange } from "@/types";
+import type { Repository, RepositoryStatus, Branch, Workspace, GitHubRepo, CommitInfo, FileChange, CommitOptions, CommitMessage, LintRules, LintIssue, AuthorEntry, AuthorMatch, SigningConfig, CommitSignature, ConfigScope, ConfigEntry, EffectiveIdentity, IncludeIf, IdentityProfile, IdentityCheck, ImportPlan, ImportResult, DiscoveryConfig, DiscoveryResult, ScanOptions, BatchOperation, BatchResult, AutoFetchConfig, StaleBranch, DeletedBranch, DeletedBranchRecord, CheckoutMode, CheckoutResult, BranchNameCheck, BranchNameInput, CompareResult, MergePreview, CommitQuery, FileHistoryEntry, FileAtRevision, BlameOptions, BlameRange } from "@/types";
 
 /**
  * Scan a directory recursively to find Git repositories
  */
-export async function scanRepositories(path: string): Promise<Repository[]> {
-    return invoke<Repository[]>("scan_repositories", { path });
+export async function scanRepositories(path: string, options?: ScanOptions): Promise<Repository[]> {
+    return invoke<Repository[]>("scan_repositories", { path, options });
+}
+
+/**
+ * Scan a directory, emitting a "repository-found" event for each repository as it is discovered
+ */
+export async function scanRepositoriesStream(path: string, scanId: string, options?: ScanOptions): Promise<Repository[]> {
+    return invoke<Repository[]>("scan_repositories_stream", { path, scanId, options });
 }
 
 /**
@@ -27,6 +34,13 @@ export async function getRepositoryInfo(path: string): Promise<Repository> {
     return invoke<Repository>("get_repository_info", { path });
 }
 
+/**
+ * Get repositories with their last commit and summary metadata, most recent activity first
+ */
+export async function getRepositorySummaries(paths: string[], force?: boolean): Promise<Repository[]> {
+    return invoke<Repository[]>("get_repository_summaries", { paths, force });
+}
+
 /**
  * List all branches in a repository
  */
@@ -62,6 +76,104 @@ export async function addRepositoryToWorkspace(
     return invoke("add_repository_to_workspace", { workspaceId, repoPath });
 }
 
+export async function renameWorkspace(workspaceId: string, name: string): Promise<void> {
+    return invoke("rename_workspace", { workspaceId, name });
+}
+
+export async function setWorkspaceColor(workspaceId: string, color: string | null): Promise<void> {
+    return invoke("set_workspace_color", { workspaceId, color });
+}
+
+export async function removeRepositoryFromWorkspace(workspaceId: string, repoPath: string): Promise<void> {
+    return invoke("remove_repository_from_workspace", { workspaceId, repoPath });
+}
+
+export async function reorderWorkspaceRepositories(workspaceId: string, order: string[]): Promise<void> {
+    return invoke("reorder_workspace_repositories", { workspaceId, order });
+}
+
+export async function moveRepositoryBetweenWorkspaces(
+    fromWorkspaceId: string,
+    toWorkspaceId: string,
+    repoPath: string,
+    index?: number
+): Promise<void> {
+    return invoke("move_repository_between_workspaces", { fromWorkspaceId, toWorkspaceId, repoPath, index });
+}
+
+export async function setRepositoryAlias(workspaceId: string, repoPath: string, alias: string | null): Promise<void> {
+    return invoke("set_repository_alias", { workspaceId, repoPath, alias });
+}
+
+export async function setRepositoryPinned(workspaceId: string, repoPath: string, pinned: boolean): Promise<void> {
+    return invoke("set_repository_pinned", { workspaceId, repoPath, pinned });
+}
+
+export async function exportWorkspace(workspaceId: string, file: string): Promise<void> {
+    return invoke("export_workspace", { workspaceId, file });
+}
+
+export async function planWorkspaceImport(file: string, root: string): Promise<ImportPlan> {
+    return invoke<ImportPlan>("plan_workspace_import", { file, root });
+}
+
+export async function importWorkspace(file: string, root: string, cloneMissing: boolean): Promise<ImportResult> {
+    return invoke<ImportResult>("import_workspace", { file, root, cloneMissing });
+}
+
+export async function setWorkspaceDiscovery(workspaceId: string, discovery: DiscoveryConfig | null): Promise<void> {
+    return invoke("set_workspace_discovery", { workspaceId, discovery });
+}
+
+export async function rescanWorkspace(workspaceId: string): Promise<DiscoveryResult> {
+    return invoke<DiscoveryResult>("rescan_workspace", { workspaceId });
+}
+
+export async function setWorkspaceAutoFetch(workspaceId: string, config: AutoFetchConfig | null): Promise<void> {
+    return invoke("set_workspace_auto_fetch", { workspaceId, config });
+}
+
+export async function setRepositoryAutoFetch(workspaceId: string, repoPath: string, enabled: boolean): Promise<void> {
+    return invoke("set_repository_auto_fetch", { workspaceId, repoPath, enabled });
+}
+
+/**
+ * Tell the background fetcher which repositories are open in the UI
+ */
+export async function setAutoFetchOpenRepositories(paths: string[]): Promise<void> {
+    return invoke("set_auto_fetch_open_repositories", { paths });
+}
+
+/**
+ * Pause background fetches while offline
+ */
+export async function setNetworkOnline(online: boolean): Promise<void> {
+    return invoke("set_network_online", { online });
+}
+
+/**
+ * Run fetch, fast-forward pull, status or an allow-listed git command on every repository of a workspace
+ */
+export async function runWorkspaceBatch(workspaceId: string, operation: BatchOperation, concurrency?: number): Promise<BatchResult[]> {
+    return invoke<BatchResult[]>("run_workspace_batch", { workspaceId, operation, concurrency });
+}
+
+export async function runRepositoriesBatch(paths: string[], operation: BatchOperation, concurrency?: number): Promise<BatchResult[]> {
+    return invoke<BatchResult[]>("run_repositories_batch", { paths, operation, concurrency });
+}
+
+export async function setWorkspaceIdentity(workspaceId: string, identity: IdentityProfile | null): Promise<void> {
+    return invoke("set_workspace_identity", { workspaceId, identity });
+}
+
+export async function checkWorkspaceIdentity(workspaceId: string): Promise<IdentityCheck[]> {
+    return invoke<IdentityCheck[]>("check_workspace_identity", { workspaceId });
+}
+
+export async function applyWorkspaceIdentity(workspaceId: string, paths?: string[]): Promise<IdentityCheck[]> {
+    return invoke<IdentityCheck[]>("apply_workspace_identity", { workspaceId, paths });
+}
+
 // ============== Git Operations ==============
 
 export async function gitFetch(path: string): Promise<void> {
@@ -80,8 +192,32 @@ export async function gitPushInitial(path: string): Promise<void> {
     return invoke("git_push_initial", { path });
 }
 
-export async function gitCommit(path: string, message: string): Promise<void> {
-    return invoke("git_commit", { path, message });
+export async function gitCommit(path: string, message: CommitMessage, options?: CommitOptions): Promise<void> {
+    return invoke("git_commit", { path, message, options });
+}
+
+export async function getCommitTemplate(path: string): Promise<CommitMessage | null> {
+    return invoke<CommitMessage | null>("get_commit_template", { path });
+}
+
+export async function lintCommitMessage(message: CommitMessage, rules?: LintRules): Promise<LintIssue[]> {
+    return invoke<LintIssue[]>("lint_commit_message", { message, rules });
+}
+
+export async function getRepositoryAuthors(path: string): Promise<AuthorEntry[]> {
+    return invoke<AuthorEntry[]>("get_repository_authors", { path });
+}
+
+export async function searchCoAuthors(path: string, query: string, limit?: number): Promise<AuthorMatch[]> {
+    return invoke<AuthorMatch[]>("search_co_authors", { path, query, limit });
+}
+
+export async function gitIsHeadPushed(path: string): Promise<boolean> {
+    return invoke<boolean>("git_is_head_pushed", { path });
+}
+
+export async function gitRewordCommit(path: string, hash: string, message: string): Promise<void> {
+    return invoke("git_reword_commit", { path, hash, message });
 }
 
 export async function gitStageFile(path: string, filePath: string): Promise<void> {
@@ -100,8 +236,12 @@ export async function gitUnstageAll(path: string): Promise<void> {
     return invoke("git_unstage_all", { path });
 }
 
-export async function gitCheckout(path: string, branch: string): Promise<void> {
-    return invoke("git_checkout", { path, branch });
+/**
+ * Checkout a branch, remote-tracking branch or commit.
+ * A safe checkout blocked by local changes returns switched: false with the files in the way.
+ */
+export async function gitCheckout(path: string, branch: string, mode?: CheckoutMode): Promise<CheckoutResult> {
+    return invoke<CheckoutResult>("git_checkout", { path, branch, mode });
 }
 
 export async function gitCreateBranch(path: string, branch: string): Promise<void> {
@@ -112,6 +252,124 @@ export async function gitDeleteBranch(path: string, branch: string, force: boole
     return invoke("git_delete_branch", { path, branch, force });
 }
 
+/**
+ * Create a branch at any ref without checking it out
+ */
+export async function gitCreateBranchAt(path: string, branch: string, startPoint: string, track?: boolean): Promise<void> {
+    return invoke("git_create_branch_at", { path, branch, startPoint, track });
+}
+
+/**
+ * Rename a local branch, and its remote branch when renameRemote is set
+ */
+export async function gitRenameBranch(path: string, oldName: string, newName: string, renameRemote: boolean): Promise<void> {
+    return invoke("git_rename_branch", { path, oldName, newName, renameRemote });
+}
+
+/**
+ * Set the upstream of a branch, or unset it with null
+ */
+export async function gitSetBranchUpstream(path: string, branch: string, upstream: string | null): Promise<void> {
+    return invoke("git_set_branch_upstream", { path, branch, upstream });
+}
+
+/**
+ * History of one file, following renames
+ *
+ * Signatures are not verified; use verifyCommitSignatures for the rows shown.
+ */
+export async function getFileHistory(path: string, filePath: string, skip?: number, limit?: number, includeDiff?: boolean): Promise<FileHistoryEntry[]> {
+    return invoke<FileHistoryEntry[]>("get_file_history", { path, filePath, skip, limit, includeDiff });
+}
+
+export async function getFileDiffInCommit(path: string, hash: string, filePath: string, oldPath?: string | null): Promise<string> {
+    return invoke<string>("get_file_diff_in_commit", { path, hash, filePath, oldPath });
+}
+
+/**
+ * Read a file as it was at a revision
+ */
+export async function getFileAtRevision(path: string, revision: string, filePath: string): Promise<FileAtRevision> {
+    return invoke<FileAtRevision>("get_file_at_revision", { path, revision, filePath });
+}
+
+/**
+ * Blame a file; pass previous_hash and previous_path of a range to blame prior to that commit
+ */
+export async function blameFile(path: string, filePath: string, options?: BlameOptions): Promise<BlameRange[]> {
+    return invoke<BlameRange[]>("blame_file", { path, filePath, options });
+}
+
+/**
+ * Blame a file, emitting "blame-ranges" events as ranges are computed
+ */
+export async function blameFileStream(path: string, filePath: string, blameId: string, options?: BlameOptions): Promise<void> {
+    return invoke("blame_file_stream", { path, filePath, blameId, options });
+}
+
+export async function cancelBlame(blameId: string): Promise<void> {
+    return invoke("cancel_blame", { blameId });
+}
+
+/**
+ * Compare base (usually HEAD) with another ref
+ */
+export async function compareRefs(path: string, base: string, target: string, limit?: number): Promise<CompareResult> {
+    return invoke<CompareResult>("compare_refs", { path, base, target, limit });
+}
+
+export async function getCompareFileDiff(path: string, base: string, target: string, filePath: string): Promise<string> {
+    return invoke<string>("get_compare_file_diff", { path, base, target, filePath });
+}
+
+/**
+ * Dry-run a merge of target into base without touching the working tree
+ */
+export async function previewMerge(path: string, base: string, target: string): Promise<MergePreview> {
+    return invoke<MergePreview>("preview_merge", { path, base, target });
+}
+
+export async function validateBranchName(path: string, name: string): Promise<BranchNameCheck> {
+    return invoke<BranchNameCheck>("validate_branch_name", { path, name });
+}
+
+/**
+ * Render a branch name template such as "feature/{ticket}-{slug}" into a valid, unused name
+ */
+export async function suggestBranchName(path: string, template: string, input: BranchNameInput): Promise<string> {
+    return invoke<string>("suggest_branch_name", { path, template, input });
+}
+
+export async function setWorkspaceBranchTemplates(workspaceId: string, templates: string[]): Promise<void> {
+    return invoke("set_workspace_branch_templates", { workspaceId, templates });
+}
+
+/**
+ * Find local branches that are gone upstream, (squash-)merged, or untouched for inactiveDays
+ */
+export async function findStaleBranches(path: string, inactiveDays?: number): Promise<StaleBranch[]> {
+    return invoke<StaleBranch[]>("find_stale_branches", { path, inactiveDays });
+}
+
+/**
+ * Force-delete branches; each tip is kept restorable through gitRestoreBranch
+ */
+export async function gitDeleteBranches(path: string, branches: string[]): Promise<DeletedBranch[]> {
+    return invoke<DeletedBranch[]>("git_delete_branches", { path, branches });
+}
+
+export async function listDeletedBranches(path: string): Promise<DeletedBranchRecord[]> {
+    return invoke<DeletedBranchRecord[]>("list_deleted_branches", { path });
+}
+
+export async function gitRestoreBranch(path: string, branch: string, tip?: string): Promise<void> {
+    return invoke("git_restore_branch", { path, branch, tip });
+}
+
+export async function gitDeleteRemoteBranch(path: string, remote: string, branch: string): Promise<void> {
+    return invoke("git_delete_remote_branch", { path, remote, branch });
+}
+
 export async function gitUndoCommit(path: string): Promise<void> {
     return invoke("git_undo_commit", { path });
 }
@@ -124,16 +382,53 @@ export async function getFileDiff(path: string, filePath: string): Promise<strin
     return invoke("get_file_diff", { path, filePath });
 }
 
-export async function getGitHistory(path: string): Promise<CommitInfo[]> {
-    return invoke<CommitInfo[]>("get_git_history", { path });
+export async function getGitHistory(path: string, skip?: number, limit?: number): Promise<CommitInfo[]> {
+    return invoke<CommitInfo[]>("get_git_history", { path, skip, limit });
 }
 
-export async function getCommitFiles(path: string, hash: string): Promise<FileChange[]> {
-    return invoke<FileChange[]>("get_commit_files", { path, hash });
+/**
+ * Add commits reachable from new refs to the on-disk history cache; returns how many were added
+ */
+export async function indexRepositoryHistory(path: string): Promise<number> {
+    return invoke<number>("index_repository_history", { path });
+}
+
+export async function clearRepositoryCache(path: string): Promise<void> {
+    return invoke("clear_repository_cache", { path });
+}
+
+/**
+ * Search history by message, author, hash prefix, path or content
+ *
+ * Signatures are not verified; use verifyCommitSignatures for the rows shown.
+ */
+export async function searchCommits(path: string, query: CommitQuery): Promise<CommitInfo[]> {
+    return invoke<CommitInfo[]>("search_commits", { path, query });
+}
+
+/**
+ * Search history, emitting "commit-search-results" events with batches as they are found
+ */
+export async function searchCommitsStream(path: string, searchId: string, query: CommitQuery): Promise<void> {
+    return invoke("search_commits_stream", { path, searchId, query });
 }
 
-export async function getCommitFileDiff(path: string, hash: string, filePath: string): Promise<string> {
-    return invoke<string>("get_commit_file_diff", { path, hash, filePath });
+export async function cancelCommitSearch(searchId: string): Promise<void> {
+    return invoke("cancel_commit_search", { searchId });
+}
+
+/**
+ * Files changed in a commit; merges are diffed against `parent` (1-based, default first)
+ */
+export async function getCommitFiles(path: string, hash: string, parent?: number): Promise<FileChange[]> {
+    return invoke<FileChange[]>("get_commit_files", { path, hash, parent });
+}
+
+/**
+ * Diff of one file in a commit; pass the old path of a rename or copy to diff against it
+ */
+export async function getCommitFileDiff(path: string, hash: string, filePath: string, parent?: number, oldPath?: string | null): Promise<string> {
+    return invoke<string>("get_commit_file_diff", { path, hash, filePath, oldPath, parent });
 }
 
 export async function gitDiscardChanges(path: string, filePath: string): Promise<void> {
@@ -164,6 +459,21 @@ export async function gitCherryPickCommit(path: string, hash: string): Promise<v
     return invoke("git_cherry_pick_commit", { path, hash });
 }
 
+export async function gitCreateTag(path: string, name: string, target: string, message: string, sign?: boolean): Promise<void> {
+    return invoke("git_create_tag", { path, name, target, message, sign });
+}
+
+export async function getSigningConfig(path: string): Promise<SigningConfig> {
+    return invoke<SigningConfig>("get_signing_config", { path });
+}
+
+/**
+ * Verify signatures of the commits on screen; search and file history results leave them unchecked
+ */
+export async function verifyCommitSignatures(path: string, hashes: string[]): Promise<Record<string, CommitSignature>> {
+    return invoke<Record<string, CommitSignature>>("verify_commit_signatures", { path, hashes });
+}
+
 export async function gitInit(path: string): Promise<void> {
     return invoke("git_init", { path });
 }
@@ -176,6 +486,42 @@ export async function gitRemoteSetUrl(path: string, name: string, url: string):
     return invoke("git_remote_set_url", { path, name, url });
 }
 
+// ============== Git Config ==============
+
+export async function listGitConfig(path: string, scope?: ConfigScope): Promise<ConfigEntry[]> {
+    return invoke<ConfigEntry[]>("list_git_config", { path, scope });
+}
+
+export async function getGitConfig(path: string, key: string): Promise<ConfigEntry | null> {
+    return invoke<ConfigEntry | null>("get_git_config", { path, key });
+}
+
+export async function setGitConfig(path: string, key: string, value: string, scope: ConfigScope): Promise<void> {
+    return invoke("set_git_config", { path, key, value, scope });
+}
+
+export async function unsetGitConfig(path: string, key: string, scope: ConfigScope): Promise<void> {
+    return invoke("unset_git_config", { path, key, scope });
+}
+
+export async function getGitIdentity(path: string): Promise<EffectiveIdentity> {
+    return invoke<EffectiveIdentity>("get_git_identity", { path });
+}
+
+export async function listGitIncludeIfs(path: string): Promise<IncludeIf[]> {
+    return invoke<IncludeIf[]>("list_git_include_ifs", { path });
+}
+
+export async function setIncludeIfIdentity(
+    directory: string,
+    includeFile: string,
+    name: string,
+    email: string,
+    signingKey?: string
+): Promise<void> {
+    return invoke("set_include_if_identity", { directory, includeFile, name, email, signingKey });
+}
+
 // ============== GitHub Integration ==============
 
 export async function createGithubRepository(
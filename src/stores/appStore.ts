@@ -4,7 +4,7 @@
  */
 
 import { create } from "zustand";
-import type { Repository, RepositoryStatus, Workspace, NavigationContext, Branch, CommitInfo, FileChange } from "@/types";
+import type { Repository, RepositoryStatus, Workspace, NavigationContext, Branch, CommitInfo, FileChange, AutoFetchEvent } from "@/types";
 import {
     getRepositoryStatus,
     getRepositoryInfo,
@@ -28,6 +28,9 @@ import {
     gitAddToGitignore,
     listBranches,
     getGitHistory,
+    indexRepositoryHistory,
+    setAutoFetchOpenRepositories,
+    setNetworkOnline as setAutoFetchNetworkOnline,
     createGithubRepository,
     addRepositoryToWorkspace,
     deleteWorkspace,
@@ -141,6 +144,8 @@ interface AppState {
     scanForRepositories: (path: string) => Promise<void>;
     refreshRepositoryStatus: () => Promise<void>;
     pollRepositoryStatus: () => Promise<void>;
+    setNetworkOnline: (online: boolean) => void;
+    handleAutoFetchEvent: (event: AutoFetchEvent) => Promise<void>;
     clearError: () => void;
     updateSettings: (settings: Partial<AppState["settings"]>) => void;
     setMergeConflictModalOpen: (open: boolean) => void;
@@ -221,6 +226,8 @@ export const useAppStore = create<AppState>((set, get) => ({
 
     setSelectedRepository: async (path) => {
         set({ selectedRepositoryPath: path, selectedFile: null, selectedFileDiff: null, isLoading: true });
+        // Open repositories are fetched in the background more eagerly than the rest
+        setAutoFetchOpenRepositories(path ? [path] : []).catch((error) => console.error("Failed to update auto-fetch:", error));
         if (path) {
             try {
                 const status = await getRepositoryStatus(path);
@@ -229,6 +236,8 @@ export const useAppStore = create<AppState>((set, get) => ({
                 const branches = allBranches.filter(b => b.name !== "HEAD");
                 set({ repositoryStatus: status, branches, isLoading: false });
                 await get().loadHistory();
+                // Warm the history cache in the background so scrolling stays instant
+                indexRepositoryHistory(path).catch((error) => console.error("Failed to index history:", error));
                 // Auto-stage all changes immediately on selection
                 if (status.unstaged.length > 0 || status.untracked.length > 0) {
                     await get().stageAll();
@@ -259,7 +268,8 @@ export const useAppStore = create<AppState>((set, get) => ({
 
             let diff;
             if (get().selectedCommitHash) {
-                diff = await getCommitFileDiff(selectedRepositoryPath, get().selectedCommitHash!, filePath);
+                const change = get().selectedCommitFiles.find(f => f.path === filePath);
+                diff = await getCommitFileDiff(selectedRepositoryPath, get().selectedCommitHash!, filePath, undefined, change?.old_path);
             } else {
                 diff = await getFileDiff(selectedRepositoryPath, filePath);
             }
@@ -317,7 +327,8 @@ export const useAppStore = create<AppState>((set, get) => ({
                     if (defaultBranch && currentBranchName !== defaultBranch.name) {
                         try {
                             // 1. Switch to default branch
-                            await gitCheckout(selectedRepositoryPath, defaultBranch.name);
+                            const result = await gitCheckout(selectedRepositoryPath, defaultBranch.name);
+                            if (!result.switched) throw new Error("local changes block the switch");
                             // 2. Delete the stale branch
                             await gitDeleteBranch(selectedRepositoryPath, currentBranchName, true);
 
@@ -409,7 +420,8 @@ export const useAppStore = create<AppState>((set, get) => ({
 
         set({ isLoading: true });
         try {
-            await gitCommit(selectedRepositoryPath, message);
+            const [subject, ...body] = message.split("\n");
+            await gitCommit(selectedRepositoryPath, { subject, body: body.join("\n") });
             const status = await getRepositoryStatus(selectedRepositoryPath);
             set({ repositoryStatus: status, isLoading: false, selectedFile: null, selectedFileDiff: null });
             await get().loadHistory();
@@ -423,7 +435,14 @@ export const useAppStore = create<AppState>((set, get) => ({
         if (!selectedRepositoryPath) return;
         set({ isLoading: true, selectedFile: null, selectedFileDiff: null });
         try {
-            await gitCheckout(selectedRepositoryPath, branch);
+            const result = await gitCheckout(selectedRepositoryPath, branch);
+            if (!result.switched) {
+                set({
+                    error: `Checkout blocked: local changes to ${result.blocked_files.join(", ")} would be overwritten. Commit, stash or discard them first.`,
+                    isLoading: false,
+                });
+                return;
+            }
             const status = await getRepositoryStatus(selectedRepositoryPath);
             set({ repositoryStatus: status, isLoading: false });
             await loadBranches();
@@ -756,7 +775,7 @@ export const useAppStore = create<AppState>((set, get) => ({
             try {
                 const status = await getRepositoryStatus(localPath);
                 if (status.untracked.length > 0 || status.unstaged.length > 0 || (templates && (templates.readme || templates.gitignore !== "" || templates.license))) {
-                    await gitCommit(localPath, "Initial commit from Pinax");
+                    await gitCommit(localPath, { subject: "Initial commit from Pinax" });
                 }
             } catch (e) {
                 console.warn("Initial commit failed:", e);
@@ -982,6 +1001,27 @@ export const useAppStore = create<AppState>((set, get) => ({
         }
     },
 
+    setNetworkOnline: (online) => {
+        setAutoFetchNetworkOnline(online).catch((error) => console.error("Failed to update network state:", error));
+    },
+
+    handleAutoFetchEvent: async (event) => {
+        const { selectedRepositoryPath } = get();
+        if (event.path !== selectedRepositoryPath) return;
+        if (event.error) {
+            set({ error: `Background fetch failed: ${event.error}` });
+            return;
+        }
+        try {
+            // New remote commits change ahead/behind counts and remote branches
+            const status = await getRepositoryStatus(event.path);
+            set({ repositoryStatus: status });
+            await get().loadBranches();
+        } catch (error) {
+            console.error("Failed to refresh after background fetch:", error);
+        }
+    },
+
     pollRepositoryStatus: async () => {
         const { selectedRepositoryPath, repositoryStatus } = get();
         if (!selectedRepositoryPath) return;
@@ -10,6 +10,35 @@ export interface Repository {
     name: string;
     remote_url?: string;
     last_commit?: CommitInfo;
+    kind: RepositoryKind;
+    summary?: RepositorySummary;
+}
+
+export interface RepositorySummary {
+    branch?: string; // Missing when HEAD is detached
+    is_dirty: boolean;
+    ahead: number;
+    behind: number;
+    default_branch?: string;
+    last_fetch?: number;
+    last_activity?: number;
+}
+
+/** Bare repositories have no working tree and are browsed read-only */
+export type RepositoryKind = "normal" | "bare" | "worktree" | "submodule";
+
+export interface ScanOptions {
+    max_depth?: number;
+    skip_patterns?: string[];
+    skip_hidden?: boolean;
+    follow_symlinks?: boolean;
+    stop_at_repositories?: boolean;
+}
+
+/** Payload of the "repository-found" event */
+export interface RepositoryFoundEvent {
+    scan_id: string;
+    repository: Repository;
 }
 
 export interface CommitInfo {
@@ -19,6 +48,72 @@ export interface CommitInfo {
     author: string;
     email: string;
     timestamp: string;
+    signature: SignatureStatus;
+    signer?: string;
+}
+
+export type SignatureStatus = "good" | "bad" | "unknown" | "expired" | "revoked" | "none";
+
+export interface CommitSignature {
+    hash: string;
+    signature: SignatureStatus;
+    signer?: string;
+}
+
+export interface SigningConfig {
+    sign_commits: boolean;
+    sign_tags: boolean;
+    format: "openpgp" | "ssh" | "x509";
+    signing_key?: string;
+}
+
+export interface Trailer {
+    key: string;
+    value: string;
+}
+
+export interface CommitMessage {
+    subject: string;
+    body?: string;
+    trailers?: Trailer[];
+}
+
+export interface LintRules {
+    max_subject_length?: number;
+    max_body_line_length?: number;
+    blank_second_line?: boolean;
+    conventional_commits?: boolean;
+    allowed_types?: string[];
+}
+
+export interface LintIssue {
+    rule: string;
+    message: string;
+    line: number;
+}
+
+export interface AuthorEntry {
+    name: string;
+    email: string;
+    commits: number;
+}
+
+export interface AuthorMatch extends AuthorEntry {
+    score: number;
+}
+
+export interface CommitOptions {
+    amend?: boolean;
+    allow_empty?: boolean;
+    no_verify?: boolean;
+    signoff?: boolean;
+    author?: string;
+    date?: string;
+    fixup?: string;
+    squash?: string;
+    sign?: boolean;
+    co_authors?: string[];
+    lint?: LintRules;
 }
 
 export interface RepositoryStatus {
@@ -30,11 +125,16 @@ export interface RepositoryStatus {
     unstaged: FileChange[];
     untracked: string[];
     conflicts: FileChange[];
+    read_only?: boolean; // Bare repository: history only, write actions are rejected
 }
 
 export interface FileChange {
     path: string;
     status: FileStatus;
+    old_path?: string | null; // Source path of a rename or copy
+    similarity?: number | null; // Percentage, for renames and copies
+    additions?: number | null; // null for binary files
+    deletions?: number | null;
 }
 
 export type FileStatus = "added" | "modified" | "deleted" | "renamed" | "copied" | "conflicted";
@@ -45,6 +145,166 @@ export interface Branch {
     is_remote: boolean;
     upstream?: string;
     track?: string;
+    last_commit_date?: string;
+    last_commit_author?: string;
+    ahead: number;
+    behind: number;
+    upstream_gone: boolean;
+    merged: boolean; // Reachable from the default branch
+}
+
+export interface BranchNameCheck {
+    name: string;
+    valid: boolean;
+    error: string | null;
+    exists_locally: boolean;
+    exists_on_remotes: string[];
+}
+
+export interface BranchNameInput {
+    ticket?: string;
+    description?: string;
+    values?: Record<string, string>; // Extra {placeholders}
+}
+
+export interface FileHistoryEntry extends CommitInfo {
+    path: string; // Path of the file in this commit
+    old_path: string | null; // Set when this commit renamed or copied the file
+    status: FileStatus;
+    diff: string | null;
+}
+
+export interface FileAtRevision {
+    revision: string;
+    path: string;
+    size: number;
+    is_binary: boolean;
+    content: string | null; // null for binary or oversized files
+}
+
+export interface BlameOptions {
+    revision?: string | null; // e.g. "<hash>^" to blame prior to a commit
+    ignore_whitespace?: boolean; // -w
+    detect_moves?: boolean; // -M
+    detect_copies?: number; // Number of -C flags, up to 3
+    use_ignore_revs?: boolean; // Honor blame.ignoreRevsFile / .git-blame-ignore-revs, default true
+}
+
+export interface BlameRange {
+    hash: string;
+    final_line: number; // 1-based
+    original_line: number;
+    line_count: number;
+    author: string;
+    author_email: string;
+    author_time: number; // Unix timestamp
+    summary: string;
+    path: string; // Path of the file in this commit
+    previous_hash: string | null; // Blame prior to this commit from here
+    previous_path: string | null;
+    boundary: boolean;
+}
+
+/** Payload of the "blame-ranges" event */
+export interface BlameEvent {
+    blame_id: string;
+    ranges: BlameRange[];
+    done: boolean;
+}
+
+export interface CommitQuery {
+    message?: string;
+    regex?: boolean; // Applies to message and content
+    case_sensitive?: boolean;
+    author?: string;
+    hash_prefix?: string; // At least 4 hex digits; matches commits on any branch
+    path?: string;
+    content?: string; // Pickaxe: -S, or -G with regex
+    all_refs?: boolean;
+    skip?: number;
+    limit?: number;
+}
+
+/** Payload of the "commit-search-results" event */
+export interface CommitSearchEvent {
+    search_id: string;
+    commits: CommitInfo[];
+    done: boolean;
+}
+
+export interface CompareResult {
+    base: string;
+    target: string;
+    merge_base: string | null;
+    base_only: CommitInfo[];
+    target_only: CommitInfo[];
+    files: FileChange[]; // Changed on target since the merge base
+}
+
+export interface MergePreview {
+    clean: boolean;
+    tree: string | null;
+    conflicts: string[];
+    messages: string[];
+}
+
+export type CheckoutMode = "safe" | "stash" | "carry" | "discard";
+
+export interface CheckoutResult {
+    switched: boolean;
+    branch: string | null; // null for a detached HEAD
+    blocked_files: string[]; // Local changes that stopped a safe checkout
+    stash: string | null;
+    conflicts: string[];
+}
+
+export type StaleReason = "upstream_gone" | "merged" | "squash_merged" | "inactive";
+
+export interface StaleBranch {
+    name: string;
+    tip: string;
+    upstream: string | null;
+    last_commit_date: string | null;
+    days_inactive: number;
+    reasons: StaleReason[];
+}
+
+export interface DeletedBranch {
+    name: string;
+    tip: string | null;
+    error: string | null;
+}
+
+export interface DeletedBranchRecord {
+    name: string;
+    tip: string;
+    remote: string | null;
+    merge: string | null;
+    deleted_at: number; // Unix timestamp
+}
+
+// ============== Config Types ==============
+
+export type ConfigScope = "system" | "global" | "local" | "worktree" | "command" | "unknown";
+
+export interface ConfigEntry {
+    key: string;
+    value: string;
+    scope: ConfigScope;
+    origin: string;
+}
+
+export interface EffectiveIdentity {
+    name?: ConfigEntry;
+    email?: ConfigEntry;
+    signing_key?: ConfigEntry;
+}
+
+export interface IncludeIf {
+    condition: string;
+    path: string;
+    scope: ConfigScope;
+    origin: string;
 }
 
 // ============== Workspace Types ==============
@@ -55,6 +315,113 @@ export interface Workspace {
     repositories: string[]; // Paths to repositories
     color?: string;
     created_at: string;
+    identity?: IdentityProfile;
+    aliases: Record<string, string>; // Display names keyed by repository path
+    pinned: string[];
+    discovery?: DiscoveryConfig;
+    missing: string[]; // Repositories whose directory disappeared
+    removed: string[]; // Repositories the user removed; discovery leaves them out
+    auto_fetch?: AutoFetchConfig;
+    branch_templates: string[]; // e.g. "feature/{ticket}-{slug}"
+}
+
+export interface AutoFetchConfig {
+    enabled: boolean;
+    interval_minutes: number;
+    excluded: string[]; // Repositories opted out of background fetches
+}
+
+/** Payload of the "auto-fetch" event */
+export interface AutoFetchEvent {
+    path: string;
+    ahead: number;
+    behind: number;
+    new_commits: number;
+    error: string | null;
+}
+
+export interface DiscoveryConfig {
+    roots: string[];
+    include: string[];
+    exclude: string[];
+    rescan_interval_minutes?: number;
+    last_scan?: number;
+}
+
+/** Payload of the "workspace-discovery" event */
+export interface DiscoveryResult {
+    workspace_id: string;
+    added: string[];
+    missing: string[];
+    restored: string[];
+}
+
+export type BatchOperation =
+    | { type: "fetch" }
+    | { type: "pull" }
+    | { type: "status" }
+    | { type: "command"; args: string[] };
+
+export interface BatchStatus {
+    branch: string;
+    is_dirty: boolean;
+    ahead: number;
+    behind: number;
+    staged: number;
+    unstaged: number;
+    untracked: number;
+    conflicts: number;
+}
+
+export interface BatchResult {
+    path: string;
+    success: boolean;
+    error: string | null;
+    status: BatchStatus | null;
+    output: string | null;
+}
+
+export interface IdentityProfile {
+    name: string;
+    email: string;
+    signing_key?: string;
+    ssh_key?: string;
+}
+
+export interface IdentityMismatch {
+    key: string;
+    expected?: string;
+    actual?: string;
+}
+
+export interface IdentityCheck {
+    path: string;
+    matches: boolean;
+    mismatches: IdentityMismatch[];
+    error?: string;
+}
+
+export type ImportStatus = "matched" | "missing" | "no_remote";
+
+export interface ImportEntry {
+    name: string;
+    remote_url?: string;
+    relative_path: string;
+    status: ImportStatus;
+    local_path?: string;
+    target_path: string;
+}
+
+export interface ImportPlan {
+    name: string;
+    color?: string;
+    entries: ImportEntry[];
+}
+
+export interface ImportResult {
+    workspace: Workspace;
+    cloned: string[];
+    failed: { name: string; error: string }[];
 }
 
 // ============== UI Types ==============